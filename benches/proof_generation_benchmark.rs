@@ -0,0 +1,162 @@
+//! Benchmarks for LazyTower proof generation cost
+
+use criterion::{black_box, criterion_group, BenchmarkId, Criterion};
+use std::time::Instant;
+
+use lazytower_rs::{Digest, LazyTower};
+
+/// Mock digest for testing
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockDigest;
+
+impl Digest for MockDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut result = b"digest(".to_vec();
+        result.extend_from_slice(item.as_ref());
+        result.extend_from_slice(b")");
+        result
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut result = b"digest_items[".to_vec();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                result.extend_from_slice(b",");
+            }
+            result.extend_from_slice(item.as_ref());
+        }
+        result.extend_from_slice(b"]");
+        result
+    }
+}
+
+/// Helper function to create a tower with n items
+fn create_tower_with_items(n: usize, width: usize) -> LazyTower<Vec<u8>, MockDigest> {
+    let mut tower = LazyTower::new(width).unwrap();
+    for i in 0..n {
+        tower.append(format!("item_{}", i).into_bytes());
+    }
+    tower
+}
+
+/// Benchmark proof generation with different numbers of items
+fn bench_generation_scaling(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generation_scaling");
+
+    let sizes = vec![10, 50, 100, 500, 1000, 5000];
+    let width = 4;
+
+    for size in sizes {
+        let tower = create_tower_with_items(size, width);
+        let middle_index = size / 2;
+
+        group.bench_with_input(BenchmarkId::new("items", size), &tower, |b, tower| {
+            b.iter(|| black_box(tower.generate_proof(black_box(middle_index))))
+        });
+    }
+    group.finish();
+}
+
+/// Benchmark proof generation with different tower widths
+fn bench_generation_width_scaling(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generation_width_scaling");
+
+    let widths = vec![2, 4, 8, 16, 32];
+    let num_items = 1000;
+
+    for width in widths {
+        let tower = create_tower_with_items(num_items, width);
+        let middle_index = num_items / 2;
+
+        group.bench_with_input(BenchmarkId::new("width", width), &tower, |b, tower| {
+            b.iter(|| black_box(tower.generate_proof(black_box(middle_index))))
+        });
+    }
+    group.finish();
+}
+
+/// Benchmark proof generation on large towers to expose asymptotic behaviour
+fn bench_generation_large(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generation_large");
+
+    let sizes = vec![10_000, 100_000];
+    let width = 4;
+
+    for size in sizes {
+        let tower = create_tower_with_items(size, width);
+        let middle_index = size / 2;
+
+        group.bench_with_input(BenchmarkId::new("items", size), &tower, |b, tower| {
+            b.iter(|| black_box(tower.generate_proof(black_box(middle_index))))
+        });
+    }
+    group.finish();
+}
+
+/// Manual timing test to measure generation cost against tower size
+fn manual_generation_timing_test() {
+    println!("\n=== Manual Proof Generation Timing Test ===");
+    println!("Testing how proof generation cost grows with the number of items");
+
+    let sizes = vec![100, 500, 1000, 2000, 5000, 10000, 50000, 100000];
+    let width = 4;
+    let iterations = 100;
+
+    println!("Size\tHeight\tAvg Time (ns)\tTime/Item (ns)\tTime/Log(n)");
+
+    for size in sizes {
+        let tower = create_tower_with_items(size, width);
+        let height = tower.height();
+        let middle_index = size / 2;
+
+        if tower.generate_proof(middle_index).is_err() {
+            println!("{}\t-\tFailed to generate proof", size);
+            continue;
+        }
+
+        // Warm up
+        for _ in 0..5 {
+            black_box(tower.generate_proof(middle_index).ok());
+        }
+
+        // Measure
+        let start = Instant::now();
+        for _ in 0..iterations {
+            black_box(tower.generate_proof(middle_index).ok());
+        }
+        let elapsed = start.elapsed();
+
+        let avg_nanos = elapsed.as_nanos() / iterations as u128;
+        let time_per_item = avg_nanos as f64 / size as f64;
+        let time_per_log = avg_nanos as f64 / (size as f64).log2();
+
+        println!(
+            "{}\t{}\t{}\t\t{:.3}\t\t{:.1}",
+            size, height, avg_nanos, time_per_item, time_per_log
+        );
+    }
+
+    println!("\nAnalysis:");
+    println!("- If generation is O(log n), Time/Log(n) should remain roughly constant");
+    println!("- If generation scans every overflow record, Time/Item stays roughly constant");
+    println!("- A flat Time/Item column points at the scan in build_proof_path_recursive");
+    println!("- MockDigest outputs grow with subtree size, so copying siblings still scales");
+}
+
+// Criterion benchmark groups
+criterion_group!(
+    benches,
+    bench_generation_scaling,
+    bench_generation_width_scaling,
+    bench_generation_large
+);
+
+// Run the manual analysis before the criterion groups when executed directly
+fn main() {
+    manual_generation_timing_test();
+
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+}
@@ -1,9 +1,10 @@
 //! Benchmarks for LazyTower proof verification to test O(1) claim
 
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Instant;
 
-use lazytower_rs::{Digest, LazyTower, MembershipProof};
+use lazytower_rs::{Digest, LazyTower, MembershipProof, TowerOptions};
 
 /// Mock digest for testing
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -32,6 +33,52 @@ impl Digest for MockDigest {
     }
 }
 
+/// Bytes fed to `ByteCountingDigest` since the last reset
+///
+/// This and the analyses below only run from `tests`, whose `#[test]` is
+/// compiled out with `harness = false`.
+#[cfg_attr(test, allow(dead_code))]
+static BYTES_HASHED: AtomicUsize = AtomicUsize::new(0);
+
+/// Fixed 32-byte toy hash that counts the bytes it consumes
+#[cfg_attr(test, allow(dead_code))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ByteCountingDigest;
+
+#[cfg_attr(test, allow(dead_code))]
+impl ByteCountingDigest {
+    fn hash(chunks: &[&[u8]]) -> [u8; 32] {
+        let mut lanes = [0xcbf29ce484222325u64, 1, 2, 3];
+        for chunk in chunks {
+            BYTES_HASHED.fetch_add(chunk.len(), Ordering::Relaxed);
+            for &byte in *chunk {
+                for (i, lane) in lanes.iter_mut().enumerate() {
+                    *lane = (*lane ^ (byte as u64 + i as u64)).wrapping_mul(0x100000001b3);
+                }
+            }
+        }
+
+        let mut out = [0u8; 32];
+        for (i, lane) in lanes.iter().enumerate() {
+            out[i * 8..(i + 1) * 8].copy_from_slice(&lane.to_le_bytes());
+        }
+        out
+    }
+}
+
+impl Digest for ByteCountingDigest {
+    type Output = [u8; 32];
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        Self::hash(&[item.as_ref()])
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let chunks: Vec<&[u8]> = items.iter().map(|item| item.as_ref()).collect();
+        Self::hash(&chunks)
+    }
+}
+
 /// Helper function to create a tower with n items
 fn create_tower_with_items(n: usize, width: usize) -> LazyTower<Vec<u8>, MockDigest> {
     let mut tower = LazyTower::new(width).unwrap();
@@ -90,6 +137,7 @@ fn bench_verification_width_scaling(c: &mut Criterion) {
 }
 
 /// Manual timing test to measure verification cost scaling
+#[cfg_attr(test, allow(dead_code))]
 fn manual_verification_timing_test() {
     println!("\n=== Manual Verification Timing Test ===");
     println!("Testing if verification cost remains constant as number of items increases");
@@ -160,6 +208,7 @@ fn bench_verification_path_length(c: &mut Criterion) {
 }
 
 /// Comprehensive analysis function
+#[cfg_attr(test, allow(dead_code))]
 fn comprehensive_verification_analysis() {
     println!("\n=== Comprehensive Verification Analysis ===");
 
@@ -209,6 +258,45 @@ fn comprehensive_verification_analysis() {
     println!("- Time/Log(n) should decrease if complexity is better than O(log n)");
 }
 
+/// Compare bytes hashed to verify a proof with raw and hashed leaves
+#[cfg_attr(test, allow(dead_code))]
+fn hashed_leaves_bytes_analysis() {
+    println!("\n=== Hashed Leaves Analysis ===");
+    println!("Bytes hashed to verify a proof for item 0 with 1 KiB items");
+
+    let width = 4;
+    let item_len = 1024;
+
+    println!("Items\tRaw leaves\tHashed leaves");
+
+    for size in [64, 1024, 16384] {
+        let mut bytes = Vec::new();
+
+        for hash_leaves in [false, true] {
+            let options = TowerOptions {
+                hash_leaves,
+                ..TowerOptions::default()
+            };
+            let mut tower: LazyTower<Vec<u8>, ByteCountingDigest> =
+                LazyTower::with_options(width, options).unwrap();
+            for i in 0..size {
+                tower.append(vec![(i % 251) as u8; item_len]);
+            }
+
+            let proof = tower.generate_proof(0).unwrap();
+            BYTES_HASHED.store(0, Ordering::Relaxed);
+            assert!(black_box(proof.verify()));
+            bytes.push(BYTES_HASHED.load(Ordering::Relaxed));
+        }
+
+        println!("{}\t{}\t\t{}", size, bytes[0], bytes[1]);
+    }
+
+    println!("\nAnalysis:");
+    println!("- Raw leaves rehash every sibling item in the bottom group");
+    println!("- Hashed leaves hash the proved item once and combine fixed-size digests");
+}
+
 // Criterion benchmark groups
 criterion_group!(
     benches,
@@ -221,11 +309,13 @@ criterion_main!(benches);
 // Also run manual tests when executed directly
 #[cfg(test)]
 mod tests {
+    #[allow(unused_imports)]
     use super::*;
 
     #[test]
     fn run_manual_timing_analysis() {
         manual_verification_timing_test();
         comprehensive_verification_analysis();
+        hashed_leaves_bytes_analysis();
     }
 }
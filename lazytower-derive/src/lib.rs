@@ -0,0 +1,82 @@
+//! Derive macro implementing `lazytower_rs::Digest` for RustCrypto hashers
+//!
+//! Enabled in `lazytower-rs` through its `derive` feature.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, LitStr, Type};
+
+/// Implement `Digest` by delegating to a RustCrypto hasher
+///
+/// The hasher is named with `#[digest(hasher = path::To::Hasher)]`, and an
+/// optional `algorithm = "name"` sets `Digest::ALGORITHM`. `digest_item`
+/// hashes the item and `digest_items` feeds every item into one hasher in
+/// order. The type must still derive `Clone`, `Debug`, `PartialEq` and `Eq`.
+#[proc_macro_derive(Digest, attributes(digest))]
+pub fn derive_digest(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut hasher: Option<Type> = None;
+    let mut algorithm: Option<LitStr> = None;
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("digest"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("hasher") {
+                hasher = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("algorithm") {
+                algorithm = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `hasher` or `algorithm`"))
+            }
+        })?;
+    }
+
+    let hasher = hasher.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "missing `#[digest(hasher = ...)]` naming a RustCrypto hasher",
+        )
+    })?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let algorithm = algorithm.map(|algorithm| {
+        quote! { const ALGORITHM: &'static str = #algorithm; }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::lazytower_rs::Digest for #name #ty_generics #where_clause {
+            type Output = ::lazytower_rs::__private::digest::Output<#hasher>;
+
+            #algorithm
+
+            const OUTPUT_LEN: Option<usize> = Some(
+                <<#hasher as ::lazytower_rs::__private::digest::OutputSizeUser>::OutputSize
+                    as ::lazytower_rs::__private::digest::typenum::Unsigned>::USIZE,
+            );
+
+            fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+                <#hasher as ::lazytower_rs::__private::digest::Digest>::digest(item.as_ref())
+            }
+
+            fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+                let mut hasher = <#hasher as ::lazytower_rs::__private::digest::Digest>::new();
+                for item in items {
+                    ::lazytower_rs::__private::digest::Digest::update(&mut hasher, item.as_ref());
+                }
+                ::lazytower_rs::__private::digest::Digest::finalize(hasher)
+            }
+        }
+    })
+}
@@ -0,0 +1,99 @@
+//! Proofs that one tower state grew into another by appending items
+
+use crate::digest::{digest_group, Digest};
+use crate::tower::TowerNode;
+
+/// Proof that appending leaves to an earlier tower state yields a later root
+///
+/// Holds every node of the tower at the earlier item count, bottom level
+/// first, and the leaves appended since. The verifier folds the earlier
+/// nodes into the earlier root, replays the appends with eager overflows at
+/// `width` and compares the result with the later root.
+///
+/// The earlier root commits only to the earlier top level, so the nodes
+/// below it are taken from the proof. A valid proof therefore shows that
+/// the later tower extends the earlier top level by the given leaves, not
+/// that the earlier tower's pending nodes were the ones claimed here.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppendProof<D: Digest> {
+    /// Width of every level
+    pub width: usize,
+    /// Number of items in the earlier tower
+    pub old_count: usize,
+    /// Nodes of the earlier tower per level, bottom first
+    pub old_levels: Vec<Vec<TowerNode<Vec<u8>, D>>>,
+    /// Leaves appended since, in order
+    pub appended: Vec<TowerNode<Vec<u8>, D>>,
+}
+
+impl<D: Digest> AppendProof<D> {
+    /// Number of items in the later tower
+    pub fn new_count(&self) -> usize {
+        self.old_count + self.appended.len()
+    }
+
+    /// Check the proof against the earlier and later roots
+    ///
+    /// Fails if the earlier nodes do not describe an eagerly overflowed
+    /// tower of `old_count` items or either root does not match.
+    pub fn verify(&self, old_root: &D::Output, new_root: &D::Output) -> bool {
+        if self.width <= 1 || !self.describes_old_count() {
+            return false;
+        }
+        if frontier_root::<D>(&self.old_levels).as_ref() != Some(old_root) {
+            return false;
+        }
+
+        let levels = self.replay();
+        frontier_root::<D>(&levels).as_ref() == Some(new_root)
+    }
+
+    /// Nodes of the later tower, from replaying the appended leaves
+    pub(crate) fn replay(&self) -> Vec<Vec<TowerNode<Vec<u8>, D>>> {
+        let mut levels = self.old_levels.clone();
+        if levels.is_empty() {
+            levels.push(Vec::new());
+        }
+
+        for leaf in &self.appended {
+            levels[0].push(leaf.clone());
+            let mut level = 0;
+            while levels[level].len() >= self.width {
+                let group: Vec<_> = levels[level].drain(..self.width).collect();
+                let digest = digest_group::<D, _>(level, &group);
+                if levels.len() <= level + 1 {
+                    levels.push(Vec::new());
+                }
+                levels[level + 1].push(TowerNode::Digest(digest));
+                level += 1;
+            }
+        }
+        levels
+    }
+
+    /// Whether the earlier nodes are the eager layout of `old_count` items
+    fn describes_old_count(&self) -> bool {
+        let mut remaining = self.old_count;
+        for nodes in &self.old_levels {
+            if nodes.len() >= self.width || remaining % self.width != nodes.len() {
+                return false;
+            }
+            remaining /= self.width;
+        }
+        remaining == 0
+    }
+}
+
+/// Root of a tower with the given nodes, as `LazyTower::root_digest` computes it
+pub(crate) fn frontier_root<D: Digest>(levels: &[Vec<TowerNode<Vec<u8>, D>>]) -> Option<D::Output> {
+    let (level, top) = levels
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(_, nodes)| !nodes.is_empty())?;
+    Some(match top.as_slice() {
+        [TowerNode::Item(item)] => D::digest_item(item),
+        [TowerNode::Digest(digest)] => digest.clone(),
+        nodes => digest_group::<D, _>(level, nodes),
+    })
+}
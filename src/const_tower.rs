@@ -0,0 +1,71 @@
+//! LazyTower with its width fixed at compile time
+
+use crate::digest::Digest;
+use crate::error::LazyTowerError;
+use crate::proof::MembershipProof;
+use crate::tower::LazyTower;
+
+/// LazyTower whose width is a const generic parameter
+///
+/// Construction cannot fail because `WIDTH` is checked at compile time.
+#[derive(Debug, Clone)]
+pub struct LazyTowerConst<T, D: Digest, const WIDTH: usize> {
+    /// The underlying tower
+    inner: LazyTower<T, D>,
+}
+
+impl<T: Clone + AsRef<[u8]>, D: Digest, const WIDTH: usize> LazyTowerConst<T, D, WIDTH> {
+    /// Compile-time check that the width is valid
+    const VALID_WIDTH: () = assert!(WIDTH > 1, "Width must be greater than 1");
+
+    /// Create a new empty tower
+    pub fn new() -> Self {
+        let () = Self::VALID_WIDTH;
+        Self {
+            inner: LazyTower::new(WIDTH).expect("width checked at compile time"),
+        }
+    }
+
+    /// Get the width of the tower
+    pub const fn width(&self) -> usize {
+        WIDTH
+    }
+
+    /// Get the total number of items in the tower
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Check if the tower is empty
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Append an item to the tower (O(1) amortized)
+    pub fn append(&mut self, item: T) {
+        self.inner.append(item);
+    }
+
+    /// Compute the root digest of the tower
+    pub fn root_digest(&self) -> Option<D::Output> {
+        self.inner.root_digest()
+    }
+
+    /// Generate a proof for an item at a given index
+    pub fn generate_proof(&self, index: usize) -> Result<MembershipProof<T, D>, LazyTowerError> {
+        self.inner.generate_proof(index)
+    }
+
+    /// Get the underlying tower
+    pub fn as_tower(&self) -> &LazyTower<T, D> {
+        &self.inner
+    }
+}
+
+impl<T: Clone + AsRef<[u8]>, D: Digest, const WIDTH: usize> Default
+    for LazyTowerConst<T, D, WIDTH>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
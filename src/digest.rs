@@ -7,11 +7,73 @@ pub trait Digest: Clone + Debug + PartialEq + Eq {
     /// The output type of the digest function
     type Output: Clone + Debug + PartialEq + Eq + AsRef<[u8]>;
 
+    /// Name of the algorithm, for verifiers outside this crate
+    const ALGORITHM: &'static str = "unknown";
+
+    /// Length in bytes of every output, for outputs whose type does not fix it
+    ///
+    /// When set, towers reject digests of any other length with
+    /// `LazyTowerError::DigestLengthMismatch` instead of building proofs that
+    /// fail to verify. `None` skips the check.
+    const OUTPUT_LEN: Option<usize> = None;
+
+    /// Identifier of the algorithm recorded in exported proofs
+    ///
+    /// Defaults to `ALGORITHM`, so implementations normally set the constant.
+    fn algorithm_id() -> &'static str {
+        Self::ALGORITHM
+    }
+
     /// Compute the digest of a single item
     fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output;
 
     /// Compute the digest of multiple items (for level computation)
     fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output;
+
+    /// Compute the digest of a group of this digest's outputs
+    ///
+    /// Used instead of `digest_items` for groups above the bottom level,
+    /// whose nodes are all digests. Defaults to `digest_items`; composite
+    /// digests override it to combine each part of their outputs separately.
+    fn digest_children<T: AsRef<[u8]>>(children: &[T]) -> Self::Output {
+        Self::digest_items(children)
+    }
+
+    /// Compute the digest of each item independently
+    ///
+    /// Defaults to `digest_item` per item; hashers with parallel or SIMD
+    /// backends can override it to hash the whole batch at once.
+    fn digest_batch(items: &[&[u8]]) -> Vec<Self::Output> {
+        items.iter().map(Self::digest_item).collect()
+    }
+
+    /// Compare two outputs, in constant time with the `constant-time` feature
+    ///
+    /// Used for the final root comparison in `ProofPath::verify`. Without the
+    /// feature this falls back to `==`.
+    fn ct_eq(a: &Self::Output, b: &Self::Output) -> bool {
+        #[cfg(feature = "constant-time")]
+        {
+            use subtle::ConstantTimeEq;
+            a.as_ref().ct_eq(b.as_ref()).into()
+        }
+        #[cfg(not(feature = "constant-time"))]
+        {
+            a == b
+        }
+    }
+}
+
+/// Digest of a group of nodes at `level`
+///
+/// Bottom groups hash the bytes of their nodes with `digest_items`; groups
+/// above them combine child digests with `digest_children`.
+pub(crate) fn digest_group<D: Digest, T: AsRef<[u8]>>(level: usize, group: &[T]) -> D::Output {
+    if level == 0 {
+        D::digest_items(group)
+    } else {
+        D::digest_children(group)
+    }
 }
 
 /// SHA256 implementation of Digest
@@ -26,6 +88,9 @@ pub mod sha256 {
     impl Digest for Sha256Digest {
         type Output = [u8; 32];
 
+        const ALGORITHM: &'static str = "sha256";
+        const OUTPUT_LEN: Option<usize> = Some(32);
+
         fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
             let mut hasher = Sha256::new();
             hasher.update(item.as_ref());
@@ -40,12 +105,367 @@ pub mod sha256 {
             hasher.finalize().into()
         }
     }
+
+    /// SHA256 as an `IncrementalDigest`, for use as `Incremental<Sha256Incremental>`
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Sha256Incremental;
+
+    impl super::incremental::IncrementalDigest for Sha256Incremental {
+        type Hasher = Sha256;
+        type Output = [u8; 32];
+
+        const ALGORITHM: &'static str = "sha256";
+        const OUTPUT_LEN: Option<usize> = Some(32);
+
+        fn new_hasher() -> Self::Hasher {
+            Sha256::new()
+        }
+
+        fn update(hasher: &mut Self::Hasher, bytes: &[u8]) {
+            Sha2Digest::update(hasher, bytes);
+        }
+
+        fn finalize(hasher: Self::Hasher) -> Self::Output {
+            Sha2Digest::finalize(hasher).into()
+        }
+    }
+}
+
+/// SHAKE256 implementation of Digest with a type-level output length
+#[cfg(feature = "shake")]
+pub mod shake {
+    use super::*;
+    use sha3::digest::{ExtendableOutput, Update, XofReader};
+    use sha3::Shake256;
+
+    /// SHAKE256 squeezed to `N` bytes
+    ///
+    /// Shorter outputs shrink proofs at the cost of collision resistance.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Shake256Digest<const N: usize>;
+
+    impl<const N: usize> Shake256Digest<N> {
+        fn squeeze(hasher: Shake256) -> [u8; N] {
+            let mut output = [0u8; N];
+            hasher.finalize_xof().read(&mut output);
+            output
+        }
+    }
+
+    impl<const N: usize> Digest for Shake256Digest<N> {
+        type Output = [u8; N];
+
+        const ALGORITHM: &'static str = "shake256";
+        const OUTPUT_LEN: Option<usize> = Some(N);
+
+        fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+            let mut hasher = Shake256::default();
+            hasher.update(item.as_ref());
+            Self::squeeze(hasher)
+        }
+
+        fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+            let mut hasher = Shake256::default();
+            for item in items {
+                hasher.update(item.as_ref());
+            }
+            Self::squeeze(hasher)
+        }
+    }
+}
+
+/// Adapter for hashers that are fed one input at a time
+pub mod incremental {
+    use super::*;
+    use std::marker::PhantomData;
+
+    /// Hash function exposed as a hasher that absorbs inputs incrementally
+    ///
+    /// Implement this instead of `Digest` when feeding a hasher chunk by chunk
+    /// is cheaper than assembling a slice of inputs, then use
+    /// `Incremental<Self>` as the tower's digest.
+    pub trait IncrementalDigest: Clone + Debug + PartialEq + Eq {
+        /// Running hasher state
+        type Hasher;
+        /// The output type of the hash function
+        type Output: Clone + Debug + PartialEq + Eq + AsRef<[u8]>;
+
+        /// Name of the algorithm, forwarded to `Digest::ALGORITHM`
+        const ALGORITHM: &'static str = "unknown";
+
+        /// Length of every output, forwarded to `Digest::OUTPUT_LEN`
+        const OUTPUT_LEN: Option<usize> = None;
+
+        /// Start a new hasher
+        fn new_hasher() -> Self::Hasher;
+
+        /// Absorb `bytes` into `hasher`
+        fn update(hasher: &mut Self::Hasher, bytes: &[u8]);
+
+        /// Finish hashing and return the digest
+        fn finalize(hasher: Self::Hasher) -> Self::Output;
+    }
+
+    /// `Digest` implementation driving an `IncrementalDigest`
+    ///
+    /// `digest_item` hashes the item alone and `digest_items` streams every
+    /// node's bytes into one hasher in order, without building an
+    /// intermediate buffer.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Incremental<H>(PhantomData<H>);
+
+    impl<H: IncrementalDigest> Digest for Incremental<H> {
+        type Output = H::Output;
+
+        const ALGORITHM: &'static str = H::ALGORITHM;
+        const OUTPUT_LEN: Option<usize> = H::OUTPUT_LEN;
+
+        fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+            let mut hasher = H::new_hasher();
+            H::update(&mut hasher, item.as_ref());
+            H::finalize(hasher)
+        }
+
+        fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+            let mut hasher = H::new_hasher();
+            for item in items {
+                H::update(&mut hasher, item.as_ref());
+            }
+            H::finalize(hasher)
+        }
+    }
+}
+
+/// Domain-separated framing around another digest
+pub mod domain {
+    use super::*;
+    use std::marker::PhantomData;
+
+    /// Tag prepended to a single item before hashing
+    pub const ITEM_TAG: u8 = 0x00;
+    /// Tag prepended to a group of items before hashing
+    pub const ITEMS_TAG: u8 = 0x01;
+
+    /// Byte order used for length and count prefixes
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Endianness {
+        Little,
+        Big,
+    }
+
+    impl Endianness {
+        /// Encode a `u64` in this byte order
+        pub fn encode_u64(self, value: u64) -> [u8; 8] {
+            match self {
+                Endianness::Little => value.to_le_bytes(),
+                Endianness::Big => value.to_be_bytes(),
+            }
+        }
+    }
+
+    /// Type-level choice of `Endianness` for a `DomainSeparated` digest
+    pub trait ByteOrder: Clone + Debug + PartialEq + Eq {
+        const ENDIANNESS: Endianness;
+    }
+
+    /// Little-endian length and count prefixes
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct LittleEndian;
+
+    impl ByteOrder for LittleEndian {
+        const ENDIANNESS: Endianness = Endianness::Little;
+    }
+
+    /// Big-endian length and count prefixes
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct BigEndian;
+
+    impl ByteOrder for BigEndian {
+        const ENDIANNESS: Endianness = Endianness::Big;
+    }
+
+    /// Digest wrapper that frames inputs so items and groups cannot collide
+    ///
+    /// A single item hashes as `ITEM_TAG || len || item`. A group hashes as
+    /// `ITEMS_TAG || count || (len || item)*`. Lengths and counts are `u64`
+    /// encoded in the byte order selected by `E`. Proof bundles report the
+    /// inner digest's `ALGORITHM`; the framing is not part of the name.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct DomainSeparated<D, E = LittleEndian>(PhantomData<(D, E)>);
+
+    impl<D: Digest, E: ByteOrder> Digest for DomainSeparated<D, E> {
+        type Output = D::Output;
+
+        const ALGORITHM: &'static str = D::ALGORITHM;
+        const OUTPUT_LEN: Option<usize> = D::OUTPUT_LEN;
+
+        fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+            let item = item.as_ref();
+            let mut framed = Vec::with_capacity(1 + 8 + item.len());
+            framed.push(ITEM_TAG);
+            framed.extend_from_slice(&E::ENDIANNESS.encode_u64(item.len() as u64));
+            framed.extend_from_slice(item);
+            D::digest_item(&framed)
+        }
+
+        fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+            let mut framed = vec![ITEMS_TAG];
+            framed.extend_from_slice(&E::ENDIANNESS.encode_u64(items.len() as u64));
+            for item in items {
+                let item = item.as_ref();
+                framed.extend_from_slice(&E::ENDIANNESS.encode_u64(item.len() as u64));
+                framed.extend_from_slice(item);
+            }
+            D::digest_item(&framed)
+        }
+    }
+
+    /// Type-level domain tag for a `Tagged` digest
+    pub trait DomainTag: Clone + Debug + PartialEq + Eq {
+        /// Bytes prepended to every digest input
+        const TAG: &'static [u8];
+    }
+
+    /// Digest wrapper that prepends a fixed domain tag to every input
+    ///
+    /// `digest_item` hashes `TAG || item`, and `digest_items` passes `TAG` as
+    /// an extra leading item, so towers built for different purposes produce
+    /// unrelated roots and proofs from one never verify against another.
+    /// Proof bundles report the inner digest's `ALGORITHM`; the tag is not
+    /// part of the name.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Tagged<Tag, D>(PhantomData<(Tag, D)>);
+
+    impl<Tag: DomainTag, D: Digest> Digest for Tagged<Tag, D> {
+        type Output = D::Output;
+
+        const ALGORITHM: &'static str = D::ALGORITHM;
+        const OUTPUT_LEN: Option<usize> = D::OUTPUT_LEN;
+
+        fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+            let mut tagged = Tag::TAG.to_vec();
+            tagged.extend_from_slice(item.as_ref());
+            D::digest_item(&tagged)
+        }
+
+        fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+            let mut tagged: Vec<&[u8]> = Vec::with_capacity(items.len() + 1);
+            tagged.push(Tag::TAG);
+            tagged.extend(items.iter().map(|item| item.as_ref()));
+            D::digest_items(&tagged)
+        }
+    }
+}
+
+/// Two digests computed side by side
+pub mod dual {
+    use super::*;
+    use std::marker::PhantomData;
+
+    /// Outputs of both halves of a `Dual` digest
+    ///
+    /// As bytes it is the first output followed by the second.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct DualOutput<A: Digest, B: Digest> {
+        first: A::Output,
+        second: B::Output,
+        bytes: Vec<u8>,
+    }
+
+    impl<A: Digest, B: Digest> DualOutput<A, B> {
+        /// Pair two outputs
+        pub fn new(first: A::Output, second: B::Output) -> Self {
+            let mut bytes = Vec::with_capacity(first.as_ref().len() + second.as_ref().len());
+            bytes.extend_from_slice(first.as_ref());
+            bytes.extend_from_slice(second.as_ref());
+            Self {
+                first,
+                second,
+                bytes,
+            }
+        }
+
+        /// Output of the first digest
+        pub fn first(&self) -> &A::Output {
+            &self.first
+        }
+
+        /// Output of the second digest
+        pub fn second(&self) -> &B::Output {
+            &self.second
+        }
+
+        /// Split into the two outputs
+        pub fn into_parts(self) -> (A::Output, B::Output) {
+            (self.first, self.second)
+        }
+    }
+
+    impl<A: Digest, B: Digest> AsRef<[u8]> for DualOutput<A, B> {
+        fn as_ref(&self) -> &[u8] {
+            &self.bytes
+        }
+    }
+
+    /// Digest committing to the same input under both `A` and `B`
+    ///
+    /// Every input is hashed by each half and the results are paired. Groups
+    /// of digests are split back into their halves, so each half combines
+    /// only its own child outputs and equals the root of a tower built with
+    /// that digest alone. Splitting needs the length of `A`'s outputs, so
+    /// `A` must set `OUTPUT_LEN`.
+    ///
+    /// Bottom-level groups hash their node bytes whole, so the halves match
+    /// single-digest towers only while level 0 holds plain items, not leaves
+    /// stored as digests.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Dual<A, B>(PhantomData<(A, B)>);
+
+    impl<A: Digest, B: Digest> Dual<A, B> {
+        /// Length of the first half of every output
+        const FIRST_LEN: usize = match A::OUTPUT_LEN {
+            Some(len) => len,
+            None => panic!("the first digest of a Dual must set OUTPUT_LEN"),
+        };
+    }
+
+    impl<A: Digest, B: Digest> Digest for Dual<A, B> {
+        type Output = DualOutput<A, B>;
+
+        const ALGORITHM: &'static str = "dual";
+        const OUTPUT_LEN: Option<usize> = match (A::OUTPUT_LEN, B::OUTPUT_LEN) {
+            (Some(first), Some(second)) => Some(first + second),
+            _ => None,
+        };
+
+        fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+            DualOutput::new(A::digest_item(item), B::digest_item(item))
+        }
+
+        fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+            DualOutput::new(A::digest_items(items), B::digest_items(items))
+        }
+
+        fn digest_children<T: AsRef<[u8]>>(children: &[T]) -> Self::Output {
+            let (firsts, seconds): (Vec<&[u8]>, Vec<&[u8]>) = children
+                .iter()
+                .map(|child| {
+                    let bytes = child.as_ref();
+                    bytes.split_at(Self::FIRST_LEN.min(bytes.len()))
+                })
+                .unzip();
+            DualOutput::new(A::digest_children(&firsts), B::digest_children(&seconds))
+        }
+    }
 }
 
 /// Mock digest for testing
 #[cfg(any(test, feature = "test-utils"))]
 pub mod mock {
     use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::marker::PhantomData;
 
     #[derive(Clone, Debug, PartialEq, Eq)]
     pub struct MockDigest;
@@ -72,6 +492,196 @@ pub mod mock {
             result
         }
     }
+
+    /// Passthrough digest exposing the exact bytes that flow into the tower
+    ///
+    /// `digest_item` returns the item unchanged and `digest_items` returns the
+    /// concatenation of its inputs, so framing problems are not hidden by hashing.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct IdentityDigest;
+
+    impl Digest for IdentityDigest {
+        type Output = Vec<u8>;
+
+        fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+            item.as_ref().to_vec()
+        }
+
+        fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+            items
+                .iter()
+                .flat_map(|item| item.as_ref().to_vec())
+                .collect()
+        }
+    }
+
+    /// Input passed to a digest call
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum DigestInput {
+        /// Bytes passed to `digest_item`
+        Item(Vec<u8>),
+        /// Bytes of each item passed to `digest_items`
+        Items(Vec<Vec<u8>>),
+    }
+
+    /// Two distinct inputs that produced the same digest output
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Collision {
+        /// The shared digest output
+        pub output: Vec<u8>,
+        /// The input that first produced the output
+        pub first: DigestInput,
+        /// The later, different input that produced it again
+        pub second: DigestInput,
+    }
+
+    thread_local! {
+        static SEEN_OUTPUTS: RefCell<HashMap<Vec<u8>, DigestInput>> = RefCell::new(HashMap::new());
+        static COLLISIONS: RefCell<Vec<Collision>> = const { RefCell::new(Vec::new()) };
+        static RECORDED: RefCell<HashMap<Vec<u8>, DigestInput>> = RefCell::new(HashMap::new());
+    }
+
+    /// Digest wrapper that records when the inner digest collides
+    ///
+    /// Every output is remembered with its input; if a different input later
+    /// produces the same output the pair is recorded as a `Collision`. State is
+    /// kept per thread and shared by every inner digest type.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct CollisionCheckDigest<D>(PhantomData<D>);
+
+    impl<D: Digest> CollisionCheckDigest<D> {
+        /// Collisions recorded on this thread since the last reset
+        pub fn collisions() -> Vec<Collision> {
+            COLLISIONS.with(|collisions| collisions.borrow().clone())
+        }
+
+        /// Forget every recorded output and collision on this thread
+        pub fn reset() {
+            SEEN_OUTPUTS.with(|seen| seen.borrow_mut().clear());
+            COLLISIONS.with(|collisions| collisions.borrow_mut().clear());
+        }
+
+        fn record(input: DigestInput, output: &D::Output) {
+            let output = output.as_ref().to_vec();
+            SEEN_OUTPUTS.with(|seen| {
+                let mut seen = seen.borrow_mut();
+                match seen.get(&output) {
+                    Some(first) if *first != input => {
+                        let collision = Collision {
+                            output,
+                            first: first.clone(),
+                            second: input,
+                        };
+                        COLLISIONS.with(|collisions| collisions.borrow_mut().push(collision));
+                    }
+                    Some(_) => {}
+                    None => {
+                        seen.insert(output, input);
+                    }
+                }
+            });
+        }
+    }
+
+    impl<D: Digest> Digest for CollisionCheckDigest<D> {
+        type Output = D::Output;
+
+        fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+            let output = D::digest_item(item);
+            Self::record(DigestInput::Item(item.as_ref().to_vec()), &output);
+            output
+        }
+
+        fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+            let output = D::digest_items(items);
+            let input = items.iter().map(|item| item.as_ref().to_vec()).collect();
+            Self::record(DigestInput::Items(input), &output);
+            output
+        }
+    }
+
+    /// Digest wrapper that records the input of every output it computes
+    ///
+    /// Following outputs back to their inputs from the root rebuilds the tree
+    /// the tower hashed, which `render` prints. State is kept per thread and
+    /// shared by every inner digest type; a later input for the same output
+    /// replaces the earlier one.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct RecordingDigest<D>(PhantomData<D>);
+
+    impl<D: Digest> RecordingDigest<D> {
+        /// Input recorded for `output` on this thread, if any
+        pub fn inputs_of(output: &[u8]) -> Option<DigestInput> {
+            RECORDED.with(|recorded| recorded.borrow().get(output).cloned())
+        }
+
+        /// Every output recorded on this thread since the last reset
+        pub fn recorded() -> HashMap<Vec<u8>, DigestInput> {
+            RECORDED.with(|recorded| recorded.borrow().clone())
+        }
+
+        /// Forget every recorded output on this thread
+        pub fn reset() {
+            RECORDED.with(|recorded| recorded.borrow_mut().clear());
+        }
+
+        /// Print the tree below `root`, one node per line indented by depth
+        ///
+        /// Bytes that are valid UTF-8 are printed as text and others as hex.
+        /// Nodes without a recorded input are printed as leaves.
+        pub fn render(root: &[u8]) -> String {
+            fn label(bytes: &[u8]) -> String {
+                match std::str::from_utf8(bytes) {
+                    Ok(text) => text.to_string(),
+                    Err(_) => bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+                }
+            }
+
+            fn walk<D: Digest>(node: &[u8], depth: usize, out: &mut String) {
+                out.push_str(&"  ".repeat(depth));
+                out.push_str(&label(node));
+                out.push('\n');
+                match RecordingDigest::<D>::inputs_of(node) {
+                    Some(DigestInput::Item(item)) => walk::<D>(&item, depth + 1, out),
+                    Some(DigestInput::Items(items)) => {
+                        for item in &items {
+                            walk::<D>(item, depth + 1, out);
+                        }
+                    }
+                    None => {}
+                }
+            }
+
+            let mut out = String::new();
+            walk::<D>(root, 0, &mut out);
+            out
+        }
+
+        fn record(input: DigestInput, output: &D::Output) {
+            RECORDED.with(|recorded| {
+                recorded
+                    .borrow_mut()
+                    .insert(output.as_ref().to_vec(), input)
+            });
+        }
+    }
+
+    impl<D: Digest> Digest for RecordingDigest<D> {
+        type Output = D::Output;
+
+        fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+            let output = D::digest_item(item);
+            Self::record(DigestInput::Item(item.as_ref().to_vec()), &output);
+            output
+        }
+
+        fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+            let output = D::digest_items(items);
+            let input = items.iter().map(|item| item.as_ref().to_vec()).collect();
+            Self::record(DigestInput::Items(input), &output);
+            output
+        }
+    }
 }
 
 #[cfg(test)]
@@ -173,4 +783,99 @@ mod tests {
         assert!(!root.is_empty());
         assert!(root.starts_with(b"digest"));
     }
+
+    #[test]
+    fn test_collision_check_no_collisions_on_overflow() {
+        use mock::{CollisionCheckDigest, MockDigest};
+        type Checked = CollisionCheckDigest<MockDigest>;
+        Checked::reset();
+
+        let mut tower: LazyTower<TestItem, Checked> = LazyTower::new(2).unwrap();
+        for i in 0..16 {
+            tower.append(TestItem(format!("item{}", i)));
+        }
+
+        assert!(tower.root_digest().is_some());
+        assert!(Checked::collisions().is_empty());
+    }
+
+    #[test]
+    fn test_collision_check_detects_ambiguous_framing() {
+        use mock::{CollisionCheckDigest, DigestInput, MockDigest};
+        type Checked = CollisionCheckDigest<MockDigest>;
+        Checked::reset();
+
+        // MockDigest joins items with commas, so these distinct groups collide
+        Checked::digest_items(&[b"A,B".to_vec()]);
+        Checked::digest_items(&[b"A".to_vec(), b"B".to_vec()]);
+
+        let collisions = Checked::collisions();
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].output, b"digest_items[A,B]");
+        assert_eq!(
+            collisions[0].first,
+            DigestInput::Items(vec![b"A,B".to_vec()])
+        );
+        assert_eq!(
+            collisions[0].second,
+            DigestInput::Items(vec![b"A".to_vec(), b"B".to_vec()])
+        );
+    }
+
+    #[test]
+    fn test_recording_digest_rebuilds_the_tree() {
+        use mock::{DigestInput, MockDigest, RecordingDigest};
+        type Recording = RecordingDigest<MockDigest>;
+        Recording::reset();
+
+        let mut tower: LazyTower<TestItem, Recording> = LazyTower::new(2).unwrap();
+        for name in ["A", "B", "C", "D"] {
+            tower.append(TestItem(name.to_string()));
+        }
+        let root = tower.root_digest().unwrap();
+
+        let left = b"digest_items[A,B]".to_vec();
+        let right = b"digest_items[C,D]".to_vec();
+        assert_eq!(
+            Recording::inputs_of(&root),
+            Some(DigestInput::Items(vec![left.clone(), right.clone()]))
+        );
+        assert_eq!(
+            Recording::inputs_of(&left),
+            Some(DigestInput::Items(vec![b"A".to_vec(), b"B".to_vec()]))
+        );
+        assert_eq!(
+            Recording::inputs_of(&right),
+            Some(DigestInput::Items(vec![b"C".to_vec(), b"D".to_vec()]))
+        );
+        assert_eq!(Recording::recorded().len(), 3);
+        assert_eq!(Recording::inputs_of(b"A"), None);
+
+        let expected: String = [
+            "digest_items[digest_items[A,B],digest_items[C,D]]",
+            "  digest_items[A,B]",
+            "    A",
+            "    B",
+            "  digest_items[C,D]",
+            "    C",
+            "    D",
+        ]
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect();
+        assert_eq!(Recording::render(&root), expected);
+    }
+
+    #[test]
+    fn test_identity_digest_exposes_level_bytes() {
+        use mock::IdentityDigest;
+        let mut tower: LazyTower<TestItem, IdentityDigest> = LazyTower::new(2).unwrap();
+        tower.append(TestItem("left".to_string()));
+        tower.append(TestItem("right".to_string()));
+
+        let level1 = tower.level(1).unwrap();
+        assert_eq!(level1.len(), 1);
+        assert_eq!(level1[0].as_ref(), b"leftright");
+        assert_eq!(IdentityDigest::digest_item(&"item"), b"item");
+    }
 }
@@ -0,0 +1,97 @@
+//! Canonical byte encodings for items that are not bytes themselves
+
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+/// Canonical encoding of `T` committed to in place of the value
+///
+/// The encoding must be deterministic: verifiers re-encode the value and the
+/// proof only holds if they get the same bytes.
+pub trait LeafEncoder<T>: Clone + Debug + PartialEq + Eq {
+    /// Encode `value` into the bytes the tower hashes
+    fn encode(value: &T) -> Vec<u8>;
+}
+
+/// Value stored with its canonical encoding
+///
+/// Dereferences to the encoded bytes through `AsRef<[u8]>`, so a tower of
+/// `Encoded` items hashes and proves the encoding while keeping the value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Encoded<T, E> {
+    value: T,
+    bytes: Vec<u8>,
+    _encoder: PhantomData<E>,
+}
+
+impl<T, E: LeafEncoder<T>> Encoded<T, E> {
+    /// Encode `value` with `E`
+    pub fn new(value: T) -> Self {
+        let bytes = E::encode(&value);
+        Self {
+            value,
+            bytes,
+            _encoder: PhantomData,
+        }
+    }
+
+    /// Get the encoded value
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Take back the encoded value
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+impl<T, E> AsRef<[u8]> for Encoded<T, E> {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// Item whose bytes are reached through `Borrow<[u8]>`
+///
+/// Towers need `AsRef<[u8]>` items. Types such as `String`, `Vec<u8>` and
+/// `Box<[u8]>` already qualify; this adapter admits types that only
+/// implement `Borrow<[u8]>`, hashing the borrowed bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BorrowedBytes<B>(pub B);
+
+impl<B> BorrowedBytes<B> {
+    /// Take back the wrapped value
+    pub fn into_inner(self) -> B {
+        self.0
+    }
+}
+
+impl<B: Borrow<[u8]>> AsRef<[u8]> for BorrowedBytes<B> {
+    fn as_ref(&self) -> &[u8] {
+        self.0.borrow()
+    }
+}
+
+/// UTF-8 encoding of string items
+///
+/// With `NORMALIZE_UTF8` the string is NFC-normalized first, so strings that
+/// differ only in how their characters are composed share a leaf. The
+/// normalizing encoder needs the `unicode` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Utf8Encoder<const NORMALIZE_UTF8: bool = false>;
+
+impl<S: AsRef<str>> LeafEncoder<S> for Utf8Encoder<false> {
+    fn encode(value: &S) -> Vec<u8> {
+        value.as_ref().as_bytes().to_vec()
+    }
+}
+
+#[cfg(feature = "unicode")]
+impl<S: AsRef<str>> LeafEncoder<S> for Utf8Encoder<true> {
+    fn encode(value: &S) -> Vec<u8> {
+        use unicode_normalization::UnicodeNormalization;
+
+        value.as_ref().nfc().collect::<String>().into_bytes()
+    }
+}
@@ -11,6 +11,26 @@ pub enum LazyTowerError {
     InvalidIndex { index: usize, max: usize },
     /// Proof generation not implemented
     ProofGenerationNotImplemented,
+    /// Internal tower state failed a consistency check
+    InconsistentState { reason: String },
+    /// Bytes for an item were not supplied
+    MissingItemBytes { index: usize },
+    /// A zero-length item was rejected by the tower options
+    EmptyItem,
+    /// An item longer than `TowerOptions::max_item_bytes` was rejected
+    ItemTooLarge { size: usize, max: usize },
+    /// A proof could not be serialized or deserialized
+    Serialization { reason: String },
+    /// Encoded proof bytes were malformed
+    Decode { reason: String },
+    /// The tower already holds `usize::MAX` items
+    CapacityExceeded,
+    /// A proof path has an impossible shape
+    MalformedProof { reason: String },
+    /// The tower's validator rejected an item
+    ValidationFailed { reason: String },
+    /// A digest's length differs from `Digest::OUTPUT_LEN`
+    DigestLengthMismatch { expected: usize, actual: usize },
 }
 
 impl fmt::Display for LazyTowerError {
@@ -33,6 +53,40 @@ impl fmt::Display for LazyTowerError {
             LazyTowerError::ProofGenerationNotImplemented => {
                 write!(f, "Proof generation is not yet implemented")
             }
+            LazyTowerError::InconsistentState { reason } => {
+                write!(f, "Inconsistent tower state: {}", reason)
+            }
+            LazyTowerError::MissingItemBytes { index } => {
+                write!(f, "No bytes supplied for item {}", index)
+            }
+            LazyTowerError::EmptyItem => {
+                write!(f, "Empty items are rejected by this tower")
+            }
+            LazyTowerError::ItemTooLarge { size, max } => {
+                write!(f, "Item of {} bytes exceeds the {} byte limit", size, max)
+            }
+            LazyTowerError::Serialization { reason } => {
+                write!(f, "Serialization failed: {}", reason)
+            }
+            LazyTowerError::Decode { reason } => {
+                write!(f, "Failed to decode proof: {}", reason)
+            }
+            LazyTowerError::CapacityExceeded => {
+                write!(f, "Tower cannot hold more than usize::MAX items")
+            }
+            LazyTowerError::MalformedProof { reason } => {
+                write!(f, "Malformed proof: {}", reason)
+            }
+            LazyTowerError::ValidationFailed { reason } => {
+                write!(f, "Item rejected by validator: {}", reason)
+            }
+            LazyTowerError::DigestLengthMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Digest output of {} bytes where {} were expected",
+                    actual, expected
+                )
+            }
         }
     }
 }
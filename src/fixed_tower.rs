@@ -0,0 +1,71 @@
+//! LazyTower of fixed-size byte arrays
+
+use crate::digest::Digest;
+use crate::error::LazyTowerError;
+use crate::proof::MembershipProof;
+use crate::tower::LazyTower;
+
+/// LazyTower whose items are `[u8; N]` arrays
+///
+/// A thin wrapper over `LazyTower<[u8; N], D>` with no storage of its own.
+/// Arrays are held inline wherever the tower keeps items, so appending one
+/// copies its bytes instead of allocating a buffer for them as `Vec<u8>`
+/// items do. The tower's maps, levels and overflow records still allocate
+/// as they grow. Suited to digests, keys and other fixed-size values.
+#[derive(Debug, Clone)]
+pub struct LazyTowerFixed<const N: usize, D: Digest> {
+    /// The underlying tower
+    inner: LazyTower<[u8; N], D>,
+}
+
+impl<const N: usize, D: Digest> LazyTowerFixed<N, D> {
+    /// Create a new empty tower with the specified width
+    pub fn new(width: usize) -> Result<Self, LazyTowerError> {
+        Ok(Self {
+            inner: LazyTower::new(width)?,
+        })
+    }
+
+    /// Get the total number of items in the tower
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Check if the tower is empty
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Append an item to the tower (O(1) amortized)
+    pub fn append(&mut self, item: [u8; N]) {
+        self.inner.append(item);
+    }
+
+    /// Get the item at `index`
+    pub fn get(&self, index: usize) -> Option<&[u8; N]> {
+        self.inner.get(index)
+    }
+
+    /// Compute the root digest of the tower
+    pub fn root_digest(&self) -> Option<D::Output> {
+        self.inner.root_digest()
+    }
+
+    /// Generate a proof for an item at a given index
+    pub fn generate_proof(
+        &self,
+        index: usize,
+    ) -> Result<MembershipProof<[u8; N], D>, LazyTowerError> {
+        self.inner.generate_proof(index)
+    }
+
+    /// Verify a proof against this tower's current root
+    pub fn verify(&self, proof: &MembershipProof<[u8; N], D>) -> bool {
+        self.inner.verify(proof)
+    }
+
+    /// Get the underlying tower
+    pub fn as_tower(&self) -> &LazyTower<[u8; N], D> {
+        &self.inner
+    }
+}
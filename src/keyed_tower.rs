@@ -0,0 +1,133 @@
+//! LazyTower accumulating key-value pairs
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::digest::Digest;
+use crate::error::LazyTowerError;
+use crate::proof::MembershipProof;
+use crate::tower::LazyTower;
+
+/// LazyTower whose items are `(key, value)` pairs
+///
+/// Each pair enters the tower as the leaf `entry_digest(key, value)`, so a
+/// proof binds the value to its key. Appending a key again adds a new leaf
+/// and later proofs for the key use the newest value.
+#[derive(Debug, Clone)]
+pub struct KeyedTower<K, V, D: Digest> {
+    /// The underlying tower of pair digests
+    inner: LazyTower<D::Output, D>,
+    /// Appended pairs in index order
+    entries: Vec<(K, V)>,
+    /// Index of the latest pair for each key
+    latest: HashMap<K, usize>,
+}
+
+/// Proof that a key maps to a value in a keyed tower
+#[derive(Debug, Clone)]
+pub struct KeyedProof<K, V, D: Digest> {
+    /// The proved key
+    pub key: K,
+    /// The value proved for the key
+    pub value: V,
+    /// Membership proof for the pair's leaf digest
+    pub proof: MembershipProof<D::Output, D>,
+}
+
+/// Leaf digest of a key-value pair
+///
+/// The key is prefixed with its length as a little-endian `u64`, so a digest
+/// that concatenates its inputs cannot confuse `("ab", "c")` with
+/// `("a", "bc")`.
+pub fn entry_digest<D: Digest>(key: &[u8], value: &[u8]) -> D::Output {
+    let key_len = (key.len() as u64).to_le_bytes();
+    D::digest_items(&[&key_len[..], key, value])
+}
+
+impl<K, V, D> KeyedTower<K, V, D>
+where
+    K: AsRef<[u8]> + Clone + Eq + Hash,
+    V: AsRef<[u8]> + Clone,
+    D: Digest,
+{
+    /// Create a new empty keyed tower with the specified width
+    pub fn new(width: usize) -> Result<Self, LazyTowerError> {
+        Ok(Self {
+            inner: LazyTower::new(width)?,
+            entries: Vec::new(),
+            latest: HashMap::new(),
+        })
+    }
+
+    /// Get the total number of pairs appended
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Check if no pairs have been appended
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Append a key-value pair, returning its index
+    pub fn append(&mut self, key: K, value: V) -> Result<usize, LazyTowerError> {
+        let index = self.inner.len();
+        self.inner
+            .append_digest(entry_digest::<D>(key.as_ref(), value.as_ref()))?;
+        self.latest.insert(key.clone(), index);
+        self.entries.push((key, value));
+        Ok(index)
+    }
+
+    /// Get the latest value appended for `key`
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.latest.get(key).map(|&index| &self.entries[index].1)
+    }
+
+    /// Compute the root digest of the tower
+    pub fn root_digest(&self) -> Option<D::Output> {
+        self.inner.root_digest()
+    }
+
+    /// Generate a proof for the latest value of `key`
+    ///
+    /// Returns `None` if the key was never appended.
+    pub fn prove_key(&self, key: &K) -> Option<KeyedProof<K, V, D>> {
+        let &index = self.latest.get(key)?;
+        let (key, value) = self.entries[index].clone();
+        let proof = self
+            .inner
+            .generate_proof(index)
+            .expect("index of a stored entry");
+        Some(KeyedProof { key, value, proof })
+    }
+
+    /// Verify a keyed proof against this tower's current root
+    ///
+    /// Unlike `KeyedProof::verify`, this also accepts the proof of a tower
+    /// holding a single pair, whose leaf is its own root.
+    pub fn verify(&self, proof: &KeyedProof<K, V, D>) -> bool {
+        proof.binds_value() && self.inner.verify(&proof.proof)
+    }
+
+    /// Get the underlying tower
+    pub fn as_tower(&self) -> &LazyTower<D::Output, D> {
+        &self.inner
+    }
+}
+
+impl<K: AsRef<[u8]>, V: AsRef<[u8]>, D: Digest> KeyedProof<K, V, D> {
+    /// Verify that the key and value hash to the proved leaf and that the
+    /// leaf is in the tower
+    pub fn verify(&self) -> bool {
+        self.binds_value() && self.proof.verify()
+    }
+
+    /// Whether the proved leaf is the digest of this key and value
+    fn binds_value(&self) -> bool {
+        D::ct_eq(
+            &entry_digest::<D>(self.key.as_ref(), self.value.as_ref()),
+            &self.proof.item,
+        )
+    }
+}
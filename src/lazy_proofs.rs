@@ -0,0 +1,126 @@
+//! On-demand proof generation for every item of a tower
+
+use crate::digest::Digest;
+use crate::proof::{MembershipProof, ProofPath};
+use crate::tower::{LazyTower, NodeId, ProofSource};
+use std::collections::HashMap;
+
+/// A tower paired with an iterator over proofs for all of its items
+///
+/// Yields `(index, proof)` in index order, building each proof only when it
+/// is requested. Path segments above a digest node are shared by every item
+/// beneath it, so the segment of every digest node, at every level, is built
+/// once and reused. A proof that cannot be built ends the iteration, so
+/// `size_hint` only bounds the remaining count from above.
+#[derive(Debug)]
+pub struct LazyProofs<T, D: Digest> {
+    /// The tower the proofs are generated from
+    tower: LazyTower<T, D>,
+    /// Root every proof commits to
+    root: Option<D::Output>,
+    /// Index of the next item to prove
+    next: usize,
+    /// Path segments from each digest node to the root, keyed by overflow record
+    segments: HashMap<usize, ProofPath<D>>,
+}
+
+impl<T: Clone + AsRef<[u8]>, D: Digest> LazyProofs<T, D> {
+    /// Create an iterator over proofs for every item in `tower`
+    pub fn new(tower: LazyTower<T, D>) -> Self {
+        let root = tower.root_digest();
+        Self {
+            tower,
+            root,
+            next: 0,
+            segments: HashMap::new(),
+        }
+    }
+
+    /// Get the tower the proofs are generated from
+    pub fn tower(&self) -> &LazyTower<T, D> {
+        &self.tower
+    }
+
+    /// Stop generating proofs and take the tower
+    pub fn into_tower(self) -> LazyTower<T, D> {
+        self.tower
+    }
+
+    /// Build the path for the item at `index`, reusing cached upper segments
+    fn path_for(&mut self, index: usize) -> Option<ProofPath<D>> {
+        let mut path = ProofPath::new();
+
+        // A lone item is its own root
+        if self.tower.len() == 1 {
+            return Some(path);
+        }
+
+        let parent = self
+            .tower
+            .proof_step(&NodeId::Item(index), &mut path)
+            .ok()?;
+        if let Some(NodeId::Digest(record)) = parent {
+            path.elements
+                .extend(self.segment(record)?.elements.iter().cloned());
+        }
+
+        Some(path)
+    }
+
+    /// Path segment from the digest of `record` to the root
+    ///
+    /// Walks up to the nearest cached segment, then caches the segment of
+    /// every record passed on the way, so each level is built once.
+    fn segment(&mut self, record: usize) -> Option<&ProofPath<D>> {
+        let mut steps = Vec::new();
+        let mut above = Some(record);
+        while let Some(current) = above.filter(|r| !self.segments.contains_key(r)) {
+            let mut step = ProofPath::new();
+            let parent = self
+                .tower
+                .proof_step(&NodeId::Digest(current), &mut step)
+                .ok()?;
+            steps.push((current, step));
+            above = match parent {
+                Some(NodeId::Digest(parent)) => Some(parent),
+                _ => None,
+            };
+        }
+
+        for (current, mut step) in steps.into_iter().rev() {
+            if let Some(parent) = above {
+                step.elements
+                    .extend(self.segments[&parent].elements.iter().cloned());
+            }
+            self.segments.insert(current, step);
+            above = Some(current);
+        }
+        self.segments.get(&record)
+    }
+}
+
+impl<T: Clone + AsRef<[u8]>, D: Digest> Iterator for LazyProofs<T, D> {
+    type Item = (usize, MembershipProof<T, D>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.next;
+        if index >= self.tower.len() {
+            return None;
+        }
+        self.next += 1;
+
+        let path = self.path_for(index)?;
+        let proof = MembershipProof {
+            item: self.tower.get(index)?.clone(),
+            path,
+            root: self.root.clone()?,
+            meta: self.tower.meta(index),
+            nonce: self.tower.nonce().map(<[u8]>::to_vec),
+        };
+        Some((index, proof))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.tower.len().saturating_sub(self.next)))
+    }
+}
@@ -2,12 +2,105 @@
 //!
 //! This implementation provides efficient proofs with configurable tower width.
 
+pub mod append_proof;
+pub mod const_tower;
 pub mod digest;
+pub mod encoder;
 pub mod error;
+pub mod fixed_tower;
+pub mod keyed_tower;
+pub mod lazy_proofs;
+pub mod policy;
 pub mod proof;
+pub mod skeleton;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_support;
 pub mod tower;
 
+pub use append_proof::AppendProof;
+pub use const_tower::LazyTowerConst;
 pub use digest::Digest;
+pub use encoder::{BorrowedBytes, Encoded, LeafEncoder, Utf8Encoder};
 pub use error::LazyTowerError;
-pub use proof::{MembershipProof, PathElement, ProofPath};
-pub use tower::{LazyTower, TowerNode};
+pub use fixed_tower::LazyTowerFixed;
+pub use keyed_tower::{KeyedProof, KeyedTower};
+pub use lazy_proofs::LazyProofs;
+#[cfg(feature = "derive")]
+pub use lazytower_derive::Digest;
+pub use policy::{EagerOverflow, LazyOverflow, OverflowPolicy};
+pub use proof::{
+    MembershipProof, PathElement, PathKind, ProofBundle, ProofPath, SiblingOrder, SparseElement,
+    SparseProofPath,
+};
+pub use skeleton::TowerSkeleton;
+pub use tower::{
+    max_items_for_height, min_height_for_items, ItemGuard, JournalEntry, LazyTower, TowerMetrics,
+    TowerNode, TowerOptions, TowerParts, TowerStats,
+};
+
+/// Common interface over tower variants
+///
+/// Lets verification pipelines be written once for any accumulator kind.
+pub trait Accumulator<T, D: Digest> {
+    /// Append an item
+    fn append(&mut self, item: T);
+
+    /// Get the total number of items
+    fn len(&self) -> usize;
+
+    /// Check if no items have been appended
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Compute the root digest
+    fn root_digest(&self) -> Option<D::Output>;
+
+    /// Generate a proof for an item at a given index
+    fn generate_proof(&self, index: usize) -> Result<MembershipProof<T, D>, LazyTowerError>;
+}
+
+impl<T: Clone + AsRef<[u8]>, D: Digest> Accumulator<T, D> for LazyTower<T, D> {
+    fn append(&mut self, item: T) {
+        LazyTower::append(self, item)
+    }
+
+    fn len(&self) -> usize {
+        LazyTower::len(self)
+    }
+
+    fn root_digest(&self) -> Option<D::Output> {
+        LazyTower::root_digest(self)
+    }
+
+    fn generate_proof(&self, index: usize) -> Result<MembershipProof<T, D>, LazyTowerError> {
+        LazyTower::generate_proof(self, index)
+    }
+}
+
+impl<T: Clone + AsRef<[u8]>, D: Digest, const WIDTH: usize> Accumulator<T, D>
+    for LazyTowerConst<T, D, WIDTH>
+{
+    fn append(&mut self, item: T) {
+        LazyTowerConst::append(self, item)
+    }
+
+    fn len(&self) -> usize {
+        LazyTowerConst::len(self)
+    }
+
+    fn root_digest(&self) -> Option<D::Output> {
+        LazyTowerConst::root_digest(self)
+    }
+
+    fn generate_proof(&self, index: usize) -> Result<MembershipProof<T, D>, LazyTowerError> {
+        LazyTowerConst::generate_proof(self, index)
+    }
+}
+
+/// Items used by code generated with `derive(Digest)`
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use ::digest;
+}
@@ -0,0 +1,33 @@
+//! Overflow policies deciding when a full level collapses
+
+use std::fmt::Debug;
+
+/// Decides whether a level holding at least `width` nodes overflows now
+///
+/// An overflow always digests the first `width` nodes of the level; the
+/// policy only chooses when that happens. It is consulted again after each
+/// collapse while the level still holds `width` or more nodes.
+pub trait OverflowPolicy: Debug + Send + Sync {
+    /// Return true to collapse the level immediately
+    fn should_overflow(&self, level: usize, current_len: usize, width: usize) -> bool;
+}
+
+/// Overflow as soon as a level reaches its width (the default)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EagerOverflow;
+
+impl OverflowPolicy for EagerOverflow {
+    fn should_overflow(&self, _level: usize, current_len: usize, width: usize) -> bool {
+        current_len >= width
+    }
+}
+
+/// Never overflow on append; levels collapse only on `LazyTower::flush`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LazyOverflow;
+
+impl OverflowPolicy for LazyOverflow {
+    fn should_overflow(&self, _level: usize, _current_len: usize, _width: usize) -> bool {
+        false
+    }
+}
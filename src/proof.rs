@@ -1,9 +1,21 @@
 //! proof structures and generation
 
-use crate::digest::Digest;
+use crate::digest::incremental::{Incremental, IncrementalDigest};
+use crate::digest::{digest_group, Digest};
+use crate::error::LazyTowerError;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// A path element in a proof
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(bound(
+        serialize = "D::Output: Serialize",
+        deserialize = "D::Output: Deserialize<'de>"
+    ))
+)]
 pub enum PathElement<D: Digest> {
     /// Siblings at the same level with position information
     Siblings {
@@ -21,15 +33,80 @@ pub enum PathElement<D: Digest> {
     },
 }
 
+/// Kind of `PathElement` to build in `ProofPath::from_levels`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathKind {
+    /// Build a `PathElement::Siblings` from digest bytes
+    Siblings,
+    /// Build a `PathElement::RawSiblings` from raw bytes
+    RawSiblings,
+}
+
+/// Order in which a path element lists the siblings of the proved node
+///
+/// The position always counts from the left of the group; only the order of
+/// the sibling list differs. Towers generate `LeftToRight` paths.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SiblingOrder {
+    /// Siblings listed from the leftmost group member
+    #[default]
+    LeftToRight,
+    /// Siblings listed from the rightmost group member
+    RightToLeft,
+}
+
+/// Wire tag for `PathElement::Siblings`
+const SIBLINGS_TAG: u8 = 0x00;
+/// Wire tag for `PathElement::RawSiblings`
+const RAW_SIBLINGS_TAG: u8 = 0x01;
+
 /// A proof path from item to root
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(bound(
+        serialize = "D::Output: Serialize",
+        deserialize = "D::Output: Deserialize<'de>"
+    ))
+)]
 pub struct ProofPath<D: Digest> {
     /// The path elements from bottom to top
     pub elements: Vec<PathElement<D>>,
 }
 
+/// A path element with its empty siblings left out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseElement<D: Digest> {
+    /// The element holding only the siblings that differ from the empty digest
+    pub element: PathElement<D>,
+    /// Bit `i % 8` of byte `i / 8` is set when sibling `i` is kept in `element`
+    pub present: Vec<u8>,
+    /// Number of siblings in the full element
+    pub sibling_count: usize,
+}
+
+/// A proof path storing only the siblings that differ from an empty digest
+///
+/// Built by `ProofPath::sparsify`. Towers padded with a canonical empty
+/// leaf carry many identical siblings, which this form replaces with one
+/// bit each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseProofPath<D: Digest> {
+    /// The sparse elements from bottom to top
+    pub elements: Vec<SparseElement<D>>,
+}
+
 /// A complete proof
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(bound(
+        serialize = "T: Serialize, D::Output: Serialize",
+        deserialize = "T: Deserialize<'de>, D::Output: Deserialize<'de>"
+    ))
+)]
 pub struct MembershipProof<T, D: Digest> {
     /// The item being proved
     pub item: T,
@@ -37,6 +114,52 @@ pub struct MembershipProof<T, D: Digest> {
     pub path: ProofPath<D>,
     /// The root digest
     pub root: D::Output,
+    /// Metadata committed alongside the item by `LazyTower::append_with_meta`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub meta: Option<u64>,
+    /// Nonce of the tower the proof came from, see `LazyTower::new_with_nonce`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nonce: Option<Vec<u8>>,
+}
+
+/// Leaf committed for an item appended with metadata
+///
+/// The leaf is `digest_items([item, meta as 8 little-endian bytes])`.
+pub(crate) fn meta_leaf<D: Digest>(item: &[u8], meta: u64) -> D::Output {
+    D::digest_items(&[item, &meta.to_le_bytes()])
+}
+
+/// Leaf committed for an item in a tower with a nonce
+///
+/// The leaf is `digest_items([nonce, leaf])`, where `leaf` is the item or
+/// its metadata leaf.
+pub(crate) fn nonce_leaf<D: Digest>(nonce: &[u8], leaf: &[u8]) -> D::Output {
+    D::digest_items(&[nonce, leaf])
+}
+
+/// A self-contained proof for verifiers that do not link this crate
+///
+/// `path` holds `ProofPath::encode` output; all other fields are plain bytes
+/// or numbers so the bundle can be checked from any language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProofBundle {
+    /// Bytes of the proved item
+    pub item: Vec<u8>,
+    /// Canonically encoded proof path
+    pub path: Vec<u8>,
+    /// Bytes of the root digest
+    pub root: Vec<u8>,
+    /// Width of the bottom level of the tower
+    pub width: usize,
+    /// Name of the digest algorithm (`Digest::algorithm_id`)
+    pub algorithm: String,
+}
+
+impl<D: Digest> Default for ProofPath<D> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<D: Digest> ProofPath<D> {
@@ -59,8 +182,367 @@ impl<D: Digest> ProofPath<D> {
             .push(PathElement::RawSiblings { position, siblings });
     }
 
+    /// Build a path from `(kind, position, siblings)` per level, bottom first
+    ///
+    /// `Siblings` entries convert each sibling's bytes into a digest and fail
+    /// with `LazyTowerError::MalformedProof` if the output type rejects them.
+    pub fn from_levels(levels: Vec<(PathKind, usize, Vec<Vec<u8>>)>) -> Result<Self, LazyTowerError>
+    where
+        D::Output: for<'a> TryFrom<&'a [u8]>,
+    {
+        let mut path = Self::new();
+        for (level, (kind, position, siblings)) in levels.into_iter().enumerate() {
+            match kind {
+                PathKind::Siblings => {
+                    let siblings = siblings
+                        .iter()
+                        .map(|bytes| {
+                            D::Output::try_from(bytes.as_slice()).map_err(|_| {
+                                LazyTowerError::MalformedProof {
+                                    reason: format!("level {} has an invalid digest", level),
+                                }
+                            })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    path.add_siblings(position, siblings);
+                }
+                PathKind::RawSiblings => path.add_raw_siblings(position, siblings),
+            }
+        }
+        Ok(path)
+    }
+
+    /// Check whether two paths fold every item to the same root
+    ///
+    /// Above level 0 a `RawSiblings` element holds digest bytes and folds
+    /// exactly like a `Siblings` element with the same bytes, so the two are
+    /// treated as equal there. At level 0 the kinds differ in meaning: raw
+    /// siblings group with the raw item, digest siblings with its digest.
+    pub fn semantic_eq(&self, other: &ProofPath<D>) -> bool {
+        fn sibling_bytes<D: Digest>(element: &PathElement<D>) -> Vec<&[u8]> {
+            match element {
+                PathElement::Siblings { siblings, .. } => {
+                    siblings.iter().map(|s| s.as_ref()).collect()
+                }
+                PathElement::RawSiblings { siblings, .. } => {
+                    siblings.iter().map(|s| s.as_slice()).collect()
+                }
+            }
+        }
+
+        self.elements.len() == other.elements.len()
+            && self
+                .elements
+                .iter()
+                .zip(&other.elements)
+                .enumerate()
+                .all(|(level, (a, b))| {
+                    let same_kind = matches!(
+                        (a, b),
+                        (PathElement::Siblings { .. }, PathElement::Siblings { .. })
+                            | (
+                                PathElement::RawSiblings { .. },
+                                PathElement::RawSiblings { .. }
+                            )
+                    );
+                    (level > 0 || same_kind)
+                        && element_shape(a).0 == element_shape(b).0
+                        && sibling_bytes(a) == sibling_bytes(b)
+                })
+    }
+
+    /// Count the trailing (root-side) elements shared with another path
+    ///
+    /// Proofs for items that share a group at some level have identical
+    /// elements above it, which batch proofs can store once.
+    pub fn share_suffix(&self, other: &ProofPath<D>) -> usize {
+        self.elements
+            .iter()
+            .rev()
+            .zip(other.elements.iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count()
+    }
+
+    /// Check that the sibling counts are possible for a tower of `widths`
+    ///
+    /// `widths` gives the width of each level from the bottom, as returned by
+    /// `LazyTower::widths`; the last entry applies to every higher level.
+    /// Every element below the top must hold exactly its level's width minus
+    /// one siblings, since only full groups overflow. The top element may be
+    /// short but always has at least one sibling, since a lone top node
+    /// contributes no element. Each proved node's position must fall among
+    /// its siblings. Proofs from a flushed tower can have short lower levels;
+    /// check those with `is_valid_flushed_shape`.
+    pub fn is_valid_shape(&self, widths: &[usize]) -> bool {
+        self.check_shape(widths, false)
+    }
+
+    /// Check the sibling counts as `is_valid_shape` does, allowing the short
+    /// lower levels that `LazyTower::flush` leaves behind
+    pub fn is_valid_flushed_shape(&self, widths: &[usize]) -> bool {
+        self.check_shape(widths, true)
+    }
+
+    fn check_shape(&self, widths: &[usize], allow_short: bool) -> bool {
+        let Some(&last) = widths.last() else {
+            return false;
+        };
+        if widths.iter().any(|&width| width <= 1) {
+            return false;
+        }
+
+        let top = self.elements.len().saturating_sub(1);
+        self.elements.iter().enumerate().all(|(level, element)| {
+            let width = widths.get(level).copied().unwrap_or(last);
+            let (position, siblings) = element_shape(element);
+            let count_ok = if level == top {
+                siblings > 0 && siblings < width
+            } else if allow_short {
+                siblings < width
+            } else {
+                siblings == width - 1
+            };
+            count_ok && position <= siblings
+        })
+    }
+
+    /// Pack each element's position into one bit, lowest level first
+    ///
+    /// In a width-2 tower every position is 0 or 1, so the bits spell the
+    /// proved item's leaf index. Other widths need more than one bit per
+    /// level and only the low bit of each position is kept. Elements past
+    /// the 64th are ignored.
+    pub fn position_bits(&self) -> u64 {
+        self.elements
+            .iter()
+            .take(u64::BITS as usize)
+            .enumerate()
+            .fold(0, |bits, (level, element)| {
+                let (position, _) = element_shape(element);
+                bits | (((position & 1) as u64) << level)
+            })
+    }
+
+    /// Encode the path in the canonical binary layout
+    ///
+    /// All integers are unsigned LEB128 varints. The layout is:
+    ///
+    /// ```text
+    /// path    := count:varint element*
+    /// element := tag:u8 position:varint count:varint sibling*
+    /// sibling := len:varint bytes
+    /// ```
+    ///
+    /// `tag` is `0x00` for `Siblings` and `0x01` for `RawSiblings`. The
+    /// format does not depend on serde and is stable across releases.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_varint(&mut out, self.elements.len());
+
+        for element in &self.elements {
+            match element {
+                PathElement::Siblings { position, siblings } => {
+                    out.push(SIBLINGS_TAG);
+                    write_varint(&mut out, *position);
+                    write_varint(&mut out, siblings.len());
+                    for sibling in siblings {
+                        write_bytes(&mut out, sibling.as_ref());
+                    }
+                }
+                PathElement::RawSiblings { position, siblings } => {
+                    out.push(RAW_SIBLINGS_TAG);
+                    write_varint(&mut out, *position);
+                    write_varint(&mut out, siblings.len());
+                    for sibling in siblings {
+                        write_bytes(&mut out, sibling);
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Decode a path produced by `encode`
+    ///
+    /// Fails with `LazyTowerError::Decode` on truncated input, unknown
+    /// tags, trailing bytes or digests the output type cannot represent.
+    pub fn decode(bytes: &[u8]) -> Result<ProofPath<D>, LazyTowerError>
+    where
+        D::Output: for<'a> TryFrom<&'a [u8]>,
+    {
+        let mut reader = Reader { bytes, offset: 0 };
+        let count = reader.varint()?;
+
+        // Every element needs at least three bytes, so cap the allocation
+        let mut elements = Vec::with_capacity(count.min(bytes.len() / 3));
+        for _ in 0..count {
+            let tag = reader.byte()?;
+            let position = reader.varint()?;
+            let sibling_count = reader.varint()?;
+
+            let element = match tag {
+                SIBLINGS_TAG => {
+                    let siblings = (0..sibling_count)
+                        .map(|_| {
+                            D::Output::try_from(reader.bytes()?).map_err(|_| {
+                                LazyTowerError::Decode {
+                                    reason: "invalid digest length".to_string(),
+                                }
+                            })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    PathElement::Siblings { position, siblings }
+                }
+                RAW_SIBLINGS_TAG => {
+                    let siblings = (0..sibling_count)
+                        .map(|_| reader.bytes().map(<[u8]>::to_vec))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    PathElement::RawSiblings { position, siblings }
+                }
+                other => {
+                    return Err(LazyTowerError::Decode {
+                        reason: format!("unknown element tag {:#04x}", other),
+                    })
+                }
+            };
+            elements.push(element);
+        }
+
+        if reader.offset != bytes.len() {
+            return Err(LazyTowerError::Decode {
+                reason: format!("{} trailing bytes", bytes.len() - reader.offset),
+            });
+        }
+
+        Ok(ProofPath { elements })
+    }
+
+    /// Drop every sibling equal to `empty`, recording where it was
+    ///
+    /// Raw siblings are compared with the bytes of `empty`.
+    pub fn sparsify(&self, empty: &D::Output) -> SparseProofPath<D> {
+        fn split<S: Clone>(siblings: &[S], is_empty: impl Fn(&S) -> bool) -> (Vec<S>, Vec<u8>) {
+            let mut present = vec![0u8; siblings.len().div_ceil(8)];
+            let mut kept = Vec::new();
+            for (i, sibling) in siblings.iter().enumerate() {
+                if !is_empty(sibling) {
+                    present[i / 8] |= 1 << (i % 8);
+                    kept.push(sibling.clone());
+                }
+            }
+            (kept, present)
+        }
+
+        let elements = self
+            .elements
+            .iter()
+            .map(|element| match element {
+                PathElement::Siblings { position, siblings } => {
+                    let (kept, present) = split(siblings, |sibling| sibling == empty);
+                    SparseElement {
+                        element: PathElement::Siblings {
+                            position: *position,
+                            siblings: kept,
+                        },
+                        present,
+                        sibling_count: siblings.len(),
+                    }
+                }
+                PathElement::RawSiblings { position, siblings } => {
+                    let (kept, present) =
+                        split(siblings, |sibling| sibling.as_slice() == empty.as_ref());
+                    SparseElement {
+                        element: PathElement::RawSiblings {
+                            position: *position,
+                            siblings: kept,
+                        },
+                        present,
+                        sibling_count: siblings.len(),
+                    }
+                }
+            })
+            .collect();
+
+        SparseProofPath { elements }
+    }
+
+    /// Keep only the first `level` elements of the path
+    ///
+    /// The result proves membership up to the digest the item's group has at
+    /// `level`, rather than up to the root. A `level` past the end of the path
+    /// keeps the whole path.
+    pub fn truncate_to_level(&self, level: usize) -> ProofPath<D> {
+        ProofPath {
+            elements: self.elements[..level.min(self.elements.len())].to_vec(),
+        }
+    }
+
+    /// Verify `item` up to the intermediate digest at `level`
+    ///
+    /// Folds only the first `level` elements and compares the result with
+    /// `intermediate`, as `verify` on `truncate_to_level(level)` would.
+    pub fn verify_to<T: AsRef<[u8]>>(
+        &self,
+        item: &T,
+        intermediate: &D::Output,
+        level: usize,
+    ) -> bool {
+        self.truncate_to_level(level).verify(item, intermediate)
+    }
+
+    /// Relist every element's siblings from order `from` in order `to`
+    ///
+    /// Positions are unchanged, so converting a path and back returns it.
+    pub fn reordered(&self, from: SiblingOrder, to: SiblingOrder) -> ProofPath<D> {
+        let mut path = self.clone();
+        if from != to {
+            for element in &mut path.elements {
+                match element {
+                    PathElement::Siblings { siblings, .. } => siblings.reverse(),
+                    PathElement::RawSiblings { siblings, .. } => siblings.reverse(),
+                }
+            }
+        }
+        path
+    }
+
+    /// Verify a path whose siblings are listed in `order`
+    ///
+    /// `verify` is `verify_ordered` with `SiblingOrder::LeftToRight`.
+    pub fn verify_ordered<T: AsRef<[u8]>>(
+        &self,
+        item: &T,
+        expected_root: &D::Output,
+        order: SiblingOrder,
+    ) -> bool {
+        match order {
+            SiblingOrder::LeftToRight => self.verify(item, expected_root),
+            SiblingOrder::RightToLeft => self
+                .reordered(order, SiblingOrder::LeftToRight)
+                .verify(item, expected_root),
+        }
+    }
+
+    /// Check that no element places the node past the end of its group
+    fn positions_in_range(&self) -> bool {
+        self.elements.iter().all(|element| {
+            let (position, siblings) = element_shape(element);
+            position <= siblings
+        })
+    }
+
     /// Verify a proof path for an item
+    ///
+    /// Paths with an element whose position lies past its siblings are
+    /// rejected outright, since reconstruction would otherwise append the
+    /// item at the end of the group instead of where the path claims.
     pub fn verify<T: AsRef<[u8]>>(&self, item: &T, expected_root: &D::Output) -> bool {
+        if !self.positions_in_range() {
+            return false;
+        }
+
         // Start with the raw item for the first level
         let mut current_is_raw = true;
         let current_raw: Option<Vec<u8>> = Some(item.as_ref().to_vec());
@@ -91,7 +573,7 @@ impl<D: Digest> ProofPath<D> {
                     }
 
                     // Compute the combined digest
-                    current_digest = Some(D::digest_items(&nodes));
+                    current_digest = Some(digest_group::<D, _>(level_idx, &nodes));
                     current_is_raw = false;
                 }
                 PathElement::RawSiblings { position, siblings } => {
@@ -137,7 +619,7 @@ impl<D: Digest> ProofPath<D> {
                         }
 
                         // Compute combined digest
-                        current_digest = Some(D::digest_items(&all_items));
+                        current_digest = Some(D::digest_children(&all_items));
                         current_is_raw = false;
                     }
                 }
@@ -151,14 +633,487 @@ impl<D: Digest> ProofPath<D> {
             current_digest.unwrap()
         };
 
-        &final_digest == expected_root
+        D::ct_eq(&final_digest, expected_root)
+    }
+
+    /// Verify a path starting from a precomputed leaf digest
+    ///
+    /// The fold starts from `leaf` instead of the item, placing it in every
+    /// group the way `verify` places a digest. For towers with `hash_leaves`,
+    /// `verify_prehashed(&D::digest_item(&item), root)` agrees with
+    /// `verify(&item, root)`. Other towers fold raw items at level 0 and never
+    /// hash an item alone, so their proofs verify here only from the leaf
+    /// node the tower holds, such as a digest added with `append_digest`.
+    pub fn verify_prehashed(&self, leaf: &D::Output, root: &D::Output) -> bool {
+        if !self.positions_in_range() {
+            return false;
+        }
+
+        let mut current = leaf.clone();
+        for (level, element) in self.elements.iter().enumerate() {
+            let group = match element {
+                PathElement::Siblings { position, siblings } => group_with(
+                    *position,
+                    current.as_ref(),
+                    siblings.iter().map(|s| s.as_ref()),
+                ),
+                PathElement::RawSiblings { position, siblings } => group_with(
+                    *position,
+                    current.as_ref(),
+                    siblings.iter().map(|s| s.as_slice()),
+                ),
+            };
+            let next = digest_group::<D, _>(level, &group);
+            current = next;
+        }
+
+        D::ct_eq(&current, root)
+    }
+
+    /// Verify like `verify`, keeping intermediate digests in `scratch`
+    ///
+    /// Each level's digest is copied into the caller's buffer and the owned
+    /// output dropped, so a verifier checking many proofs reuses one buffer
+    /// for the running digest. Groups of up to `INLINE_GROUP` nodes are
+    /// gathered as slices on the stack, but `D` still hashes each group
+    /// through `digest_items`, which may allocate; `verify_streamed` avoids
+    /// that for `Incremental` digests. The final digest is compared with
+    /// `Digest::ct_eq`, as in `verify`. `scratch` is cleared first; its
+    /// contents afterwards are unspecified.
+    pub fn verify_into<T: AsRef<[u8]>>(
+        &self,
+        item: &T,
+        expected_root: &D::Output,
+        scratch: &mut Vec<u8>,
+    ) -> bool {
+        scratch.clear();
+        if !self.positions_in_range() {
+            return false;
+        }
+        // Whether `scratch` holds a digest yet; until then the item is raw
+        let mut started = false;
+        let top = self.elements.len().checked_sub(1);
+
+        for (level_idx, element) in self.elements.iter().enumerate() {
+            let digest = match element {
+                PathElement::Siblings { position, siblings } => {
+                    if !started {
+                        scratch.extend_from_slice(D::digest_item(item).as_ref());
+                    }
+                    let siblings = siblings.iter().map(|s| s.as_ref());
+                    digest_group_with::<D>(level_idx, *position, scratch, siblings)
+                }
+                PathElement::RawSiblings { position, siblings } => {
+                    let siblings = siblings.iter().map(|s| s.as_slice());
+                    if level_idx == 0 {
+                        // First level: siblings are raw items
+                        digest_group_with::<D>(0, *position, item.as_ref(), siblings)
+                    } else {
+                        if !started {
+                            scratch.extend_from_slice(D::digest_item(item).as_ref());
+                        }
+                        digest_group_with::<D>(level_idx, *position, scratch, siblings)
+                    }
+                }
+            };
+
+            if Some(level_idx) == top {
+                return D::ct_eq(&digest, expected_root);
+            }
+            scratch.clear();
+            scratch.extend_from_slice(digest.as_ref());
+            started = true;
+        }
+
+        D::ct_eq(&D::digest_item(item), expected_root)
+    }
+}
+
+impl<H: IncrementalDigest> ProofPath<Incremental<H>> {
+    /// Verify like `verify`, streaming each group through a single hasher
+    ///
+    /// The running digest and its siblings are fed to the hasher in group
+    /// order, so no group is assembled and only the previous level's
+    /// `H::Output` is kept between levels. Verification allocates nothing when
+    /// `H`'s hasher and output do not.
+    pub fn verify_streamed<T: AsRef<[u8]>>(&self, item: &T, expected_root: &H::Output) -> bool {
+        if !self.positions_in_range() {
+            return false;
+        }
+        let mut current: Option<H::Output> = None;
+
+        for (level_idx, element) in self.elements.iter().enumerate() {
+            let leaf;
+            let node: &[u8] = match (&current, element) {
+                (Some(digest), _) => digest.as_ref(),
+                // First level: the item sits raw among raw siblings
+                (None, PathElement::RawSiblings { .. }) if level_idx == 0 => item.as_ref(),
+                (None, _) => {
+                    leaf = Incremental::<H>::digest_item(item);
+                    leaf.as_ref()
+                }
+            };
+
+            let mut hasher = H::new_hasher();
+            match element {
+                PathElement::Siblings { position, siblings } => stream_group::<H>(
+                    &mut hasher,
+                    *position,
+                    node,
+                    siblings.iter().map(|s| s.as_ref()),
+                ),
+                PathElement::RawSiblings { position, siblings } => stream_group::<H>(
+                    &mut hasher,
+                    *position,
+                    node,
+                    siblings.iter().map(|s| s.as_slice()),
+                ),
+            }
+            current = Some(H::finalize(hasher));
+        }
+
+        match current {
+            Some(root) => Incremental::<H>::ct_eq(&root, expected_root),
+            None => Incremental::<H>::ct_eq(&Incremental::<H>::digest_item(item), expected_root),
+        }
+    }
+}
+
+impl<D: Digest> SparseProofPath<D> {
+    /// Restore the full path by filling every missing sibling with `empty`
+    ///
+    /// Fails with `LazyTowerError::MalformedProof` if an element's bitmap
+    /// does not match its sibling count or kept siblings.
+    pub fn expand(&self, empty: &D::Output) -> Result<ProofPath<D>, LazyTowerError> {
+        fn fill<S: Clone>(
+            level: usize,
+            present: &[u8],
+            count: usize,
+            kept: &[S],
+            empty: S,
+        ) -> Result<Vec<S>, LazyTowerError> {
+            let malformed = || LazyTowerError::MalformedProof {
+                reason: format!(
+                    "level {} has a bitmap inconsistent with its siblings",
+                    level
+                ),
+            };
+            if present.len() != count.div_ceil(8) {
+                return Err(malformed());
+            }
+
+            let mut kept = kept.iter();
+            let mut siblings = Vec::with_capacity(count);
+            for i in 0..count {
+                if present[i / 8] & (1 << (i % 8)) != 0 {
+                    siblings.push(kept.next().ok_or_else(malformed)?.clone());
+                } else {
+                    siblings.push(empty.clone());
+                }
+            }
+            if kept.next().is_some() {
+                return Err(malformed());
+            }
+            Ok(siblings)
+        }
+
+        let mut path = ProofPath::new();
+        for (level, sparse) in self.elements.iter().enumerate() {
+            match &sparse.element {
+                PathElement::Siblings { position, siblings } => {
+                    let siblings = fill(
+                        level,
+                        &sparse.present,
+                        sparse.sibling_count,
+                        siblings,
+                        empty.clone(),
+                    )?;
+                    path.add_siblings(*position, siblings);
+                }
+                PathElement::RawSiblings { position, siblings } => {
+                    let siblings = fill(
+                        level,
+                        &sparse.present,
+                        sparse.sibling_count,
+                        siblings,
+                        empty.as_ref().to_vec(),
+                    )?;
+                    path.add_raw_siblings(*position, siblings);
+                }
+            }
+        }
+        Ok(path)
+    }
+
+    /// Verify a sparse path for an item, filling missing siblings with `empty`
+    pub fn verify<T: AsRef<[u8]>>(
+        &self,
+        item: &T,
+        expected_root: &D::Output,
+        empty: &D::Output,
+    ) -> bool {
+        self.expand(empty)
+            .is_ok_and(|path| path.verify(item, expected_root))
+    }
+}
+
+/// Place `current` among `siblings` at `position`, as `verify` does
+fn group_with<'a>(
+    position: usize,
+    current: &'a [u8],
+    siblings: impl ExactSizeIterator<Item = &'a [u8]>,
+) -> Vec<&'a [u8]> {
+    let len = siblings.len();
+    let mut siblings = siblings;
+    let mut group = Vec::with_capacity(len + 1);
+    for i in 0..=len {
+        if i == position {
+            group.push(current);
+        } else if let Some(sibling) = siblings.next() {
+            group.push(sibling);
+        }
+    }
+    group
+}
+
+/// Largest group `ProofPath::verify_into` assembles without allocating
+pub const INLINE_GROUP: usize = 16;
+
+/// Digest of `current` placed among `siblings` at `position` on `level`
+///
+/// Groups of up to `INLINE_GROUP` nodes are gathered in a stack array; larger
+/// ones fall back to `group_with`.
+fn digest_group_with<'a, D: Digest>(
+    level: usize,
+    position: usize,
+    current: &'a [u8],
+    siblings: impl ExactSizeIterator<Item = &'a [u8]>,
+) -> D::Output {
+    let len = siblings.len() + 1;
+    if len > INLINE_GROUP {
+        return digest_group::<D, _>(level, &group_with(position, current, siblings));
+    }
+
+    let mut group: [&[u8]; INLINE_GROUP] = [&[]; INLINE_GROUP];
+    let mut siblings = siblings;
+    for (i, slot) in group[..len].iter_mut().enumerate() {
+        if i == position {
+            *slot = current;
+        } else if let Some(sibling) = siblings.next() {
+            *slot = sibling;
+        }
+    }
+    digest_group::<D, _>(level, &group[..len])
+}
+
+/// Feed `current` placed among `siblings` at `position` into `hasher`
+fn stream_group<'a, H: IncrementalDigest>(
+    hasher: &mut H::Hasher,
+    position: usize,
+    current: &[u8],
+    mut siblings: impl Iterator<Item = &'a [u8]>,
+) {
+    for sibling in siblings.by_ref().take(position) {
+        H::update(hasher, sibling);
+    }
+    H::update(hasher, current);
+    for sibling in siblings {
+        H::update(hasher, sibling);
+    }
+}
+
+/// Position and sibling count of a path element
+fn element_shape<D: Digest>(element: &PathElement<D>) -> (usize, usize) {
+    match element {
+        PathElement::Siblings { position, siblings } => (*position, siblings.len()),
+        PathElement::RawSiblings { position, siblings } => (*position, siblings.len()),
+    }
+}
+
+/// Append an unsigned LEB128 varint
+fn write_varint(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Append a varint length prefix followed by the bytes
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(out, bytes.len());
+    out.extend_from_slice(bytes);
+}
+
+/// Cursor over an encoded proof path
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn truncated() -> LazyTowerError {
+        LazyTowerError::Decode {
+            reason: "unexpected end of input".to_string(),
+        }
+    }
+
+    fn byte(&mut self) -> Result<u8, LazyTowerError> {
+        let byte = *self.bytes.get(self.offset).ok_or_else(Self::truncated)?;
+        self.offset += 1;
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<usize, LazyTowerError> {
+        let mut value: usize = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            let chunk = (byte & 0x7f) as usize;
+
+            // Reject varints whose bits do not fit in a usize
+            if shift >= usize::BITS || (chunk << shift) >> shift != chunk {
+                return Err(LazyTowerError::Decode {
+                    reason: "varint overflow".to_string(),
+                });
+            }
+            value |= chunk << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], LazyTowerError> {
+        let len = self.varint()?;
+        let end = self.offset.checked_add(len).ok_or_else(Self::truncated)?;
+        let slice = self
+            .bytes
+            .get(self.offset..end)
+            .ok_or_else(Self::truncated)?;
+        self.offset = end;
+        Ok(slice)
     }
 }
 
 impl<T: Clone + AsRef<[u8]>, D: Digest> MembershipProof<T, D> {
+    /// Build a proof from received parts, rejecting impossible path shapes
+    ///
+    /// Every element must place the proved node at most one past its
+    /// siblings, and the top element must have at least one sibling. This
+    /// only rules out contradictory paths; call `verify` to check the proof.
+    pub fn try_new(item: T, path: ProofPath<D>, root: D::Output) -> Result<Self, LazyTowerError> {
+        for (level, element) in path.elements.iter().enumerate() {
+            let (position, siblings) = element_shape(element);
+            if position > siblings {
+                return Err(LazyTowerError::MalformedProof {
+                    reason: format!(
+                        "level {} places the node at {} among {} siblings",
+                        level, position, siblings
+                    ),
+                });
+            }
+        }
+        if let Some(top) = path.elements.last() {
+            if element_shape(top).1 == 0 {
+                return Err(LazyTowerError::MalformedProof {
+                    reason: "top level has no siblings".to_string(),
+                });
+            }
+        }
+
+        Ok(Self {
+            item,
+            path,
+            root,
+            meta: None,
+            nonce: None,
+        })
+    }
+
     /// Verify the proof
     pub fn verify(&self) -> bool {
-        self.path.verify(&self.item, &self.root)
+        self.verify_root(&self.root)
+    }
+
+    /// Leaf index the path proves, for proofs from width-2 towers
+    ///
+    /// Reads the index from `ProofPath::position_bits`. Returns `None` when an
+    /// element holds more than one sibling or a position past 1, or the path
+    /// has more levels than `usize` has bits. The proof does not record its
+    /// tower's width, so a proof from a wider tower whose elements happen to
+    /// hold single siblings still yields an index; only trust the result for
+    /// width-2 towers.
+    pub fn proved_index(&self) -> Option<usize> {
+        if self.path.elements.len() > usize::BITS as usize {
+            return None;
+        }
+        let binary = self.path.elements.iter().all(|element| {
+            let (position, siblings) = element_shape(element);
+            siblings <= 1 && position <= 1
+        });
+        binary.then(|| self.path.position_bits() as usize)
+    }
+
+    /// Find the first of several candidate roots the proof verifies against
+    ///
+    /// Useful when any of a few recent roots is acceptable, such as around a
+    /// reorg. The proof's own `root` field is ignored.
+    pub fn verify_against_any(&self, roots: &[D::Output]) -> Option<usize> {
+        roots.iter().position(|root| self.verify_root(root))
+    }
+
+    /// Verify the item and path against `root`
+    fn verify_root(&self, root: &D::Output) -> bool {
+        let leaf = match (self.meta, &self.nonce) {
+            (None, None) => return self.path.verify(&self.item, root),
+            (Some(meta), None) => meta_leaf::<D>(self.item.as_ref(), meta),
+            (None, Some(nonce)) => nonce_leaf::<D>(nonce, self.item.as_ref()),
+            (Some(meta), Some(nonce)) => {
+                nonce_leaf::<D>(nonce, meta_leaf::<D>(self.item.as_ref(), meta).as_ref())
+            }
+        };
+        // A lone leaf is the root itself unless leaves are hashed
+        self.path.verify(&leaf, root) || (self.path.elements.is_empty() && D::ct_eq(&leaf, root))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, D: Digest> MembershipProof<T, D>
+where
+    T: Serialize + for<'de> Deserialize<'de>,
+    D::Output: Serialize + for<'de> Deserialize<'de>,
+{
+    /// Serialize the proof to a JSON string
+    pub fn to_json(&self) -> Result<String, LazyTowerError> {
+        serde_json::to_string(self).map_err(|err| LazyTowerError::Serialization {
+            reason: err.to_string(),
+        })
+    }
+
+    /// Deserialize a proof from a JSON string
+    pub fn from_json(s: &str) -> Result<Self, LazyTowerError> {
+        serde_json::from_str(s).map_err(|err| LazyTowerError::Serialization {
+            reason: err.to_string(),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ProofBundle {
+    /// Serialize the bundle to a JSON string
+    pub fn to_json(&self) -> Result<String, LazyTowerError> {
+        serde_json::to_string(self).map_err(|err| LazyTowerError::Serialization {
+            reason: err.to_string(),
+        })
+    }
+
+    /// Deserialize a bundle from a JSON string
+    pub fn from_json(s: &str) -> Result<Self, LazyTowerError> {
+        serde_json::from_str(s).map_err(|err| LazyTowerError::Serialization {
+            reason: err.to_string(),
+        })
     }
 }
 
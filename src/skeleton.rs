@@ -0,0 +1,173 @@
+//! Item-free tower structure for generating proofs from external item bytes
+
+use crate::digest::Digest;
+use crate::error::LazyTowerError;
+use crate::proof::{meta_leaf, nonce_leaf, MembershipProof, ProofPath};
+use crate::tower::{NodeId, OverflowRecord, ProofSource};
+use std::collections::{BTreeMap, HashMap};
+
+/// A node retained by a skeleton
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SkeletonNode<D: Digest> {
+    /// An item, identified by its append index
+    Item(usize),
+    /// A digest of nodes from a lower level
+    Digest(D::Output),
+}
+
+/// The shape of a tower without copies of its items
+///
+/// A skeleton keeps the digests, overflow records and counts of a tower so a
+/// proof server can generate proofs while streaming item bytes from storage.
+#[derive(Debug, Clone)]
+pub struct TowerSkeleton<D: Digest> {
+    /// Width of the tower the skeleton was taken from
+    pub(crate) width: usize,
+    /// Total number of items appended
+    pub(crate) item_count: usize,
+    /// Current levels with items replaced by their indices
+    pub(crate) levels: Vec<Vec<SkeletonNode<D>>>,
+    /// Root digest at the time the skeleton was taken
+    pub(crate) root: Option<D::Output>,
+    /// Overflow records to track digests
+    pub(crate) overflow_records: Vec<OverflowRecord<D>>,
+    /// Mapping from NodeId to the index of the overflow record that digested it
+    pub(crate) node_records: HashMap<NodeId, usize>,
+    /// Mapping from level and index to NodeId for current nodes
+    pub(crate) level_nodes: BTreeMap<(usize, usize), NodeId>,
+    /// Whether the tower hashed items before they entered level 0
+    pub(crate) hash_leaves: bool,
+    /// Nonce mixed into every leaf of the tower, see `LazyTower::new_with_nonce`
+    pub(crate) nonce: Option<Vec<u8>>,
+    /// Metadata of items appended with `LazyTower::append_with_meta`
+    pub(crate) metadata: HashMap<usize, u64>,
+}
+
+/// A skeleton paired with externally supplied item bytes
+struct SkeletonSource<'a, D: Digest, B> {
+    skeleton: &'a TowerSkeleton<D>,
+    item_bytes: &'a [B],
+}
+
+impl<D: Digest> TowerSkeleton<D> {
+    /// Get the width of the tower
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the total number of items in the tower
+    pub fn len(&self) -> usize {
+        self.item_count
+    }
+
+    /// Check if the tower is empty
+    pub fn is_empty(&self) -> bool {
+        self.item_count == 0
+    }
+
+    /// Get the root digest of the tower
+    pub fn root_digest(&self) -> Option<&D::Output> {
+        self.root.as_ref()
+    }
+
+    /// Generate a proof for the item at `index`
+    ///
+    /// `item_bytes` holds the bytes of every item in append order. Only the
+    /// proved item and the items sharing its bottom-level group are read.
+    /// Leaves are rebuilt with the tower's nonce and each item's metadata,
+    /// which the proof carries.
+    pub fn prove<B: AsRef<[u8]>>(
+        &self,
+        index: usize,
+        item_bytes: &[B],
+    ) -> Result<MembershipProof<Vec<u8>, D>, LazyTowerError> {
+        // Check bounds
+        if self.item_count == 0 || index >= self.item_count {
+            return Err(LazyTowerError::InvalidIndex {
+                index,
+                max: self.item_count,
+            });
+        }
+
+        let source = SkeletonSource {
+            skeleton: self,
+            item_bytes,
+        };
+
+        let item = item_bytes
+            .get(index)
+            .map(|bytes| bytes.as_ref().to_vec())
+            .ok_or(LazyTowerError::MissingItemBytes { index })?;
+
+        let root = self
+            .root
+            .clone()
+            .ok_or(LazyTowerError::ProofGenerationNotImplemented)?;
+
+        // Build the proof path
+        let mut path = ProofPath::new();
+
+        // Simple case: if there's only one item, no siblings needed
+        if self.item_count == 1 {
+            return Ok(MembershipProof {
+                item,
+                path,
+                root,
+                meta: self.metadata.get(&index).copied(),
+                nonce: self.nonce.clone(),
+            });
+        }
+
+        source.build_proof_path(&NodeId::Item(index), &mut path)?;
+
+        Ok(MembershipProof {
+            item,
+            path,
+            root,
+            meta: self.metadata.get(&index).copied(),
+            nonce: self.nonce.clone(),
+        })
+    }
+}
+
+impl<D: Digest, B: AsRef<[u8]>> ProofSource<D> for SkeletonSource<'_, D, B> {
+    fn overflow_records(&self) -> &[OverflowRecord<D>] {
+        &self.skeleton.overflow_records
+    }
+
+    fn node_records(&self) -> &HashMap<NodeId, usize> {
+        &self.skeleton.node_records
+    }
+
+    fn level_nodes(&self) -> &BTreeMap<(usize, usize), NodeId> {
+        &self.skeleton.level_nodes
+    }
+
+    fn item_bytes(&self, index: usize) -> Option<Vec<u8>> {
+        let item = self.item_bytes.get(index)?.as_ref();
+        let bytes = match self.skeleton.metadata.get(&index) {
+            Some(&meta) => meta_leaf::<D>(item, meta).as_ref().to_vec(),
+            None => item.to_vec(),
+        };
+        Some(match &self.skeleton.nonce {
+            Some(nonce) => nonce_leaf::<D>(nonce, &bytes).as_ref().to_vec(),
+            None => bytes,
+        })
+    }
+
+    fn level_bytes(&self, level: usize) -> Option<Vec<Vec<u8>>> {
+        self.skeleton
+            .levels
+            .get(level)?
+            .iter()
+            .map(|node| match node {
+                SkeletonNode::Item(index) => self.item_bytes(*index),
+                SkeletonNode::Digest(digest) => Some(digest.as_ref().to_vec()),
+            })
+            .collect()
+    }
+
+    fn hashes_leaves(&self) -> bool {
+        self.skeleton.hash_leaves
+    }
+}
@@ -0,0 +1,150 @@
+//! Fixtures for checking a `Digest` implementation against the tower
+
+use crate::digest::Digest;
+use crate::tower::{LazyTower, TowerNode};
+
+/// A node of the reference fold with the number of items up to its last one
+type CountedNode<D> = (TowerNode<Vec<u8>, D>, usize);
+
+/// Item appended at `index` by `assert_root_consistency`
+pub fn fixture_item(index: usize) -> Vec<u8> {
+    format!("item-{}", index).into_bytes()
+}
+
+/// Root of a uniform tower of `items`, folded without the tower's code
+///
+/// Levels overflow eagerly into `digest_items` of their nodes at the bottom
+/// and `digest_children` above it. Returns the
+/// root with the number of leading items it covers, or `None` for no items.
+pub fn reference_root<D: Digest>(items: &[Vec<u8>], width: usize) -> Option<(D::Output, usize)> {
+    let mut levels: Vec<Vec<CountedNode<D>>> = Vec::new();
+
+    for (index, item) in items.iter().enumerate() {
+        let mut node = (TowerNode::Item(item.clone()), index + 1);
+        let mut level = 0;
+        loop {
+            if levels.len() <= level {
+                levels.push(Vec::new());
+            }
+            levels[level].push(node);
+            if levels[level].len() < width {
+                break;
+            }
+            let group: Vec<_> = levels[level].drain(..).collect();
+            let nodes: Vec<_> = group.iter().map(|(node, _)| node).collect();
+            let digest = if level == 0 {
+                D::digest_items(&nodes)
+            } else {
+                D::digest_children(&nodes)
+            };
+            node = (TowerNode::Digest(digest), index + 1);
+            level += 1;
+        }
+    }
+
+    let (level, top) = levels
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(_, nodes)| !nodes.is_empty())?;
+    let covered = top.last()?.1;
+    let root = match top.as_slice() {
+        [(TowerNode::Item(item), _)] => D::digest_item(item),
+        [(TowerNode::Digest(digest), _)] => digest.clone(),
+        nodes => {
+            let nodes: Vec<_> = nodes.iter().map(|(node, _)| node).collect();
+            if level == 0 {
+                D::digest_items(&nodes)
+            } else {
+                D::digest_children(&nodes)
+            }
+        }
+    };
+    Some((root, covered))
+}
+
+/// Check that towers hashed with `D` agree with the reference fold
+///
+/// For every width in `widths` and every size from 1 to `max_items`, builds
+/// a tower of `fixture_item`s and asserts that its root matches
+/// `reference_root`, that `verify_root_against_items` accepts the items, and
+/// that the proof of every item the root covers verifies. Crates with their
+/// own `Digest` can call this from a test to check it works with the tower.
+///
+/// # Panics
+///
+/// Panics on the first disagreement, naming the width, size and index.
+pub fn assert_root_consistency<D: Digest>(max_items: usize, widths: &[usize]) {
+    for &width in widths {
+        let mut tower = LazyTower::<Vec<u8>, D>::new(width)
+            .unwrap_or_else(|err| panic!("width {}: {}", width, err));
+        let mut items = Vec::new();
+
+        for size in 1..=max_items {
+            let item = fixture_item(size - 1);
+            tower.append(item.clone());
+            items.push(item);
+
+            let (expected, covered) =
+                reference_root::<D>(&items, width).expect("at least one item");
+            assert_eq!(
+                tower.root_digest(),
+                Some(expected),
+                "width {}, {} items: root differs from the reference",
+                width,
+                size
+            );
+            assert!(
+                tower.verify_root_against_items(&items),
+                "width {}, {} items: verify_root_against_items failed",
+                width,
+                size
+            );
+
+            for index in 0..covered {
+                let proof = tower.generate_proof(index).unwrap_or_else(|err| {
+                    panic!("width {}, {} items, index {}: {}", width, size, index, err)
+                });
+                assert!(
+                    proof.verify() && tower.verify(&proof),
+                    "width {}, {} items: proof for index {} does not verify",
+                    width,
+                    size,
+                    index
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::mock::MockDigest;
+
+    #[test]
+    fn test_root_consistency_with_mock_digest() {
+        assert_root_consistency::<MockDigest>(40, &[2, 3, 4, 5, 8]);
+    }
+
+    #[cfg(feature = "sha256")]
+    #[test]
+    fn test_root_consistency_with_sha256() {
+        use crate::digest::sha256::Sha256Digest;
+        assert_root_consistency::<Sha256Digest>(40, &[2, 3, 4, 5, 8]);
+    }
+
+    #[test]
+    fn test_reference_root_covers_top_level() {
+        let items: Vec<Vec<u8>> = (0..5).map(fixture_item).collect();
+        let (root, covered) = reference_root::<MockDigest>(&items, 2).unwrap();
+
+        // Items 0-3 fill level 2; item 4 waits at level 0
+        assert_eq!(covered, 4);
+        assert_eq!(
+            root,
+            b"digest_items[digest_items[item-0,item-1],digest_items[item-2,item-3]]"
+        );
+        assert_eq!(reference_root::<MockDigest>(&[], 2), None);
+    }
+}
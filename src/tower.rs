@@ -1,10 +1,19 @@
 //! Core LazyTower implementation
 
-use crate::digest::Digest;
+use crate::append_proof::{frontier_root, AppendProof};
+use crate::digest::{digest_group, Digest};
+use crate::encoder::{BorrowedBytes, Encoded, LeafEncoder};
 use crate::error::LazyTowerError;
-use crate::proof::{MembershipProof, ProofPath};
-use std::collections::HashMap;
+use crate::lazy_proofs::LazyProofs;
+use crate::policy::{EagerOverflow, OverflowPolicy};
+use crate::proof::{meta_leaf, nonce_leaf, MembershipProof, ProofBundle, ProofPath};
+use crate::skeleton::{SkeletonNode, TowerSkeleton};
+use std::borrow::{Borrow, Cow};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{self, Read};
 use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, MutexGuard};
 
 /// A node in the tower that can be either an item or a digest
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -24,6 +33,22 @@ impl<T: AsRef<[u8]>, D: Digest> AsRef<[u8]> for TowerNode<T, D> {
     }
 }
 
+impl<T, D: Digest> TowerNode<T, D> {
+    /// Create a digest node
+    ///
+    /// Provided instead of `From<D::Output>`, which would overlap with
+    /// `From<T>` when items are themselves digests.
+    pub fn digest(digest: D::Output) -> Self {
+        TowerNode::Digest(digest)
+    }
+}
+
+impl<T, D: Digest> From<T> for TowerNode<T, D> {
+    fn from(item: T) -> Self {
+        TowerNode::Item(item)
+    }
+}
+
 /// Position of an item in the tower
 #[derive(Debug, Clone)]
 struct ItemPosition {
@@ -35,43 +60,395 @@ struct ItemPosition {
 
 /// Node identifier for tracking nodes through levels
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
-enum NodeId {
+pub(crate) enum NodeId {
     /// Original item by index
     Item(usize),
-    /// Digest created from other nodes
-    Digest(Vec<NodeId>),
+    /// Digest created by the overflow record at this index
+    Digest(usize),
 }
 
 /// Overflow record to track which items were digested together
 #[derive(Debug, Clone)]
-struct OverflowRecord<D: Digest> {
+pub(crate) struct OverflowRecord<D: Digest> {
     /// The level that overflowed
-    level: usize,
+    pub(crate) level: usize,
     /// The node IDs that were digested together
-    node_ids: Vec<NodeId>,
+    pub(crate) node_ids: Vec<NodeId>,
     /// The resulting digest
-    result_digest: D::Output,
+    pub(crate) result_digest: D::Output,
+}
+
+/// Optional behaviour for a LazyTower
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TowerOptions {
+    /// Reject zero-length items with `LazyTowerError::EmptyItem`
+    pub reject_empty_items: bool,
+    /// Hash each item with `digest_item` before it enters level 0
+    ///
+    /// Every node is then a digest, and groups combine digests only:
+    /// a bottom group is `digest_items([digest_item(item), ...])` and a lone
+    /// item's root is `digest_item(item)`. Level 0 proofs carry sibling leaf
+    /// digests rather than raw sibling items, which keeps them small when
+    /// items are large. In a width-2 tower this is the pairwise
+    /// `combine(H(a), H(b))` convention, with `combine` being `digest_items`.
+    pub hash_leaves: bool,
+    /// Reject items longer than this many bytes with
+    /// `LazyTowerError::ItemTooLarge`; unbounded when `None`
+    pub max_item_bytes: Option<usize>,
+}
+
+/// Operation counters accumulated while appending
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TowerMetrics {
+    /// Number of items appended
+    pub total_appends: u64,
+    /// Number of level overflows
+    pub total_overflows: u64,
+    /// Number of `digest_items` or `digest_children` calls made to compute
+    /// overflow digests
+    ///
+    /// Digests taken from the group memo count as calls too.
+    pub total_digest_items_calls: u64,
+}
+
+/// Summary of a tower's shape and root, returned by `LazyTower::stats`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TowerStats<D: Digest> {
+    /// Number of items appended
+    pub item_count: usize,
+    /// Number of nodes currently held across all levels
+    pub live_count: usize,
+    /// Physical height, as returned by `height`
+    pub height: usize,
+    /// Height up to the highest node, as returned by `logical_height`
+    pub logical_height: usize,
+    /// Number of overflows performed so far
+    pub overflow_count: usize,
+    /// Width of level 0
+    pub width: usize,
+    /// Current root digest
+    pub root: Option<D::Output>,
+}
+
+/// Proofs memoised by (index, item_count)
+#[derive(Debug)]
+struct ProofCache<T, D: Digest> {
+    proofs: Mutex<HashMap<(usize, usize), MembershipProof<T, D>>>,
+}
+
+impl<T: Clone, D: Digest> ProofCache<T, D> {
+    fn new() -> Self {
+        Self {
+            proofs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Lock the map, recovering it if a previous holder panicked
+    fn lock(&self) -> MutexGuard<'_, HashMap<(usize, usize), MembershipProof<T, D>>> {
+        self.proofs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn get(&self, key: (usize, usize)) -> Option<MembershipProof<T, D>> {
+        self.lock().get(&key).cloned()
+    }
+
+    fn insert(&self, key: (usize, usize), proof: MembershipProof<T, D>) {
+        self.lock().insert(key, proof);
+    }
+
+    fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+impl<T: Clone, D: Digest> Clone for ProofCache<T, D> {
+    fn clone(&self) -> Self {
+        Self {
+            proofs: Mutex::new(self.lock().clone()),
+        }
+    }
+}
+
+/// Digest of each distinct group, keyed by its level and the bytes of its nodes
+type GroupDigests<D> = HashMap<(usize, Vec<Vec<u8>>), <D as Digest>::Output>;
+
+/// Overflow digests keyed by the level and bytes of the group they combine
+///
+/// Keyed by content rather than position within a level, so entries never
+/// go stale and clones of a tower share one memo.
+#[derive(Debug, Clone)]
+struct GroupMemo<D: Digest> {
+    digests: Arc<Mutex<GroupDigests<D>>>,
+}
+
+impl<D: Digest> GroupMemo<D> {
+    fn new() -> Self {
+        Self {
+            digests: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Get the digest of `group` at `level`, computing it only for unseen groups
+    fn digest(&self, level: usize, group: &[&[u8]]) -> D::Output {
+        let key: Vec<Vec<u8>> = group.iter().map(|node| node.to_vec()).collect();
+        self.digests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry((level, key))
+            .or_insert_with(|| digest_group::<D, _>(level, group))
+            .clone()
+    }
+
+    fn len(&self) -> usize {
+        self.digests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len()
+    }
+}
+
+/// Combined digest of a multi-node top level, kept until the levels change
+#[derive(Debug)]
+struct TopDigestCache<D: Digest> {
+    digest: Mutex<Option<D::Output>>,
+}
+
+impl<D: Digest> TopDigestCache<D> {
+    fn new() -> Self {
+        Self {
+            digest: Mutex::new(None),
+        }
+    }
+
+    /// Lock the digest, recovering it if a previous holder panicked
+    fn lock(&self) -> MutexGuard<'_, Option<D::Output>> {
+        self.digest
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Get the cached digest or compute and store it
+    fn get_or_compute(&self, compute: impl FnOnce() -> D::Output) -> D::Output {
+        self.lock().get_or_insert_with(compute).clone()
+    }
+
+    fn clear(&self) {
+        *self.lock() = None;
+    }
+}
+
+impl<D: Digest> Clone for TopDigestCache<D> {
+    fn clone(&self) -> Self {
+        Self {
+            digest: Mutex::new(self.lock().clone()),
+        }
+    }
+}
+
+/// A root recorded after an append
+#[derive(Debug, Clone)]
+struct HistoryEntry<D: Digest> {
+    /// Item count after the append
+    item_count: usize,
+    /// Root after the append
+    root: D::Output,
+    /// Number of leading items the root covers
+    covered: usize,
+}
+
+/// Recorded roots, indexed by root bytes for collision checks
+#[derive(Debug, Clone)]
+struct History<D: Digest> {
+    /// Entries from oldest to newest
+    entries: Vec<HistoryEntry<D>>,
+    /// First (covered, item_count) seen for each distinct covered count, per root
+    roots: HashMap<Vec<u8>, Vec<(usize, usize)>>,
+}
+
+impl<D: Digest> Default for History<D> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            roots: HashMap::new(),
+        }
+    }
+}
+
+impl<D: Digest> History<D> {
+    /// Earliest retained entry with `root` that covered other than `covered` items
+    fn collision(&self, root: &D::Output, covered: usize) -> Option<usize> {
+        self.roots
+            .get(root.as_ref())?
+            .iter()
+            .find(|(seen, _)| *seen != covered)
+            .map(|(_, item_count)| *item_count)
+    }
+
+    /// Append an entry, indexing its root
+    fn push(&mut self, entry: HistoryEntry<D>) {
+        let seen = self.roots.entry(entry.root.as_ref().to_vec()).or_default();
+        if seen.iter().all(|(covered, _)| *covered != entry.covered) {
+            seen.push((entry.covered, entry.item_count));
+        }
+        self.entries.push(entry);
+    }
+
+    /// Keep only the newest `keep` entries and rebuild the index over them
+    fn truncate_front(&mut self, keep: usize) {
+        let excess = self.entries.len().saturating_sub(keep);
+        if excess == 0 {
+            return;
+        }
+        let entries: Vec<_> = self.entries.drain(excess..).collect();
+        *self = Self::default();
+        for entry in entries {
+            self.push(entry);
+        }
+    }
+}
+
+/// Callback receiving (root, earlier item_count, item_count) on a root collision
+type RootCollisionFn<D> = dyn Fn(&<D as Digest>::Output, usize, usize) + Send + Sync;
+
+/// Hook set by `LazyTower::on_root_collision`
+#[derive(Clone)]
+struct RootCollisionHook<D: Digest>(Arc<RootCollisionFn<D>>);
+
+impl<D: Digest> std::fmt::Debug for RootCollisionHook<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RootCollisionHook(..)")
+    }
+}
+
+/// Callback receiving the tower, the overflowed level and the new digest
+type OverflowFn<T, D> = dyn Fn(&LazyTower<T, D>, usize, &<D as Digest>::Output) + Send + Sync;
+
+/// Hook set by `LazyTower::on_overflow`
+struct OverflowHook<T, D: Digest>(Arc<OverflowFn<T, D>>);
+
+impl<T, D: Digest> Clone for OverflowHook<T, D> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T, D: Digest> std::fmt::Debug for OverflowHook<T, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("OverflowHook(..)")
+    }
+}
+
+/// Reads an item as a leaf digest, or `None` if its bytes are not one
+type ItemDigestFn<T, D> = fn(&T) -> Option<<D as Digest>::Output>;
+
+/// Check run on every item before it is appended
+type ValidatorFn<T> = dyn Fn(&T) -> Result<(), String> + Send + Sync;
+
+/// Validator set by `LazyTower::set_validator`
+struct Validator<T>(Arc<ValidatorFn<T>>);
+
+impl<T> Clone for Validator<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> std::fmt::Debug for Validator<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Validator(..)")
+    }
+}
+
+/// One append recorded by a tower's journal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry {
+    /// Index the item was appended at
+    pub index: usize,
+    /// Bytes of the item
+    pub bytes: Vec<u8>,
+    /// Metadata the item was appended with, if any
+    pub meta: Option<u64>,
+    /// Whether the item was appended as a leaf digest with `append_digest`
+    pub digest: bool,
+}
+
+/// Configuration, items and root of a tower, for export and import
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TowerParts<T, D: Digest> {
+    /// Width of each level from the bottom
+    pub widths: Vec<usize>,
+    /// Optional behaviour of the tower
+    pub options: TowerOptions,
+    /// Items in append order
+    pub items: Vec<T>,
+    /// Root recorded alongside the items
+    pub root: Option<D::Output>,
 }
 
 /// LazyTower data structure with configurable width
+///
+/// Levels, node tracking, the journal and root history live behind `Arc`s,
+/// so `clone` is cheap and shares them until either copy mutates; the
+/// mutating copy then takes its own copy of what it changes.
 #[derive(Debug, Clone)]
 pub struct LazyTower<T, D: Digest> {
-    /// Width of the tower (number of items per level before overflow)
-    width: usize,
+    /// Width of each level from the bottom (number of nodes before overflow);
+    /// the last entry applies to every higher level
+    widths: Vec<usize>,
+    /// Optional behaviour selected at construction
+    options: TowerOptions,
+    /// Decides when a full level collapses
+    overflow_policy: Arc<dyn OverflowPolicy>,
     /// Levels of the tower, where levels[0] is the bottom level
-    levels: Vec<Vec<TowerNode<T, D>>>,
+    levels: Arc<Vec<Vec<TowerNode<T, D>>>>,
     /// Total number of items appended
     item_count: usize,
     /// Storage for original items (for proof generation)
-    items: HashMap<usize, T>,
+    items: Arc<HashMap<usize, T>>,
+    /// Metadata of items appended with `append_with_meta`
+    metadata: Arc<HashMap<usize, u64>>,
+    /// Indices of leaves appended with `append_digest`, which are committed
+    /// as the digests themselves rather than as items
+    digest_leaves: Arc<BTreeSet<usize>>,
+    /// Reads an item back as the digest it was appended as; set by
+    /// `append_digest`, where items are digests
+    item_digest: Option<ItemDigestFn<T, D>>,
+    /// Bytes mixed into every leaf, set by `new_with_nonce`
+    nonce: Option<Vec<u8>>,
     /// Mapping from item index to its current position in the tower
-    item_positions: HashMap<usize, ItemPosition>,
+    item_positions: Arc<HashMap<usize, ItemPosition>>,
     /// Overflow records to track digests
-    overflow_records: Vec<OverflowRecord<D>>,
-    /// Mapping from digest to the NodeIds it contains
-    digest_to_nodes: HashMap<Vec<u8>, Vec<NodeId>>,
-    /// Mapping from level and index to NodeId for current nodes
-    level_nodes: HashMap<(usize, usize), NodeId>,
+    overflow_records: Arc<Vec<OverflowRecord<D>>>,
+    /// Mapping from a digest node to the NodeIds it contains, keyed by node
+    /// rather than digest bytes since equal digests can sit at several positions
+    digest_to_nodes: Arc<HashMap<NodeId, Vec<NodeId>>>,
+    /// Mapping from level and index to NodeId for current nodes, ordered so
+    /// scans over it visit nodes in the same order on every run
+    level_nodes: Arc<BTreeMap<(usize, usize), NodeId>>,
+    /// Mapping from NodeId to the index of the overflow record that digested it
+    node_records: Arc<HashMap<NodeId, usize>>,
+    /// Root after each append as (item_count, root), when history is enabled
+    history: Option<Arc<History<D>>>,
+    /// Every append in order, when the journal is enabled
+    journal: Option<Arc<Vec<JournalEntry>>>,
+    /// Called when an append reproduces a root from a different tower shape
+    root_collision_hook: Option<RootCollisionHook<D>>,
+    /// Called for each overflow once the operation causing it has finished
+    overflow_hook: Option<OverflowHook<T, D>>,
+    /// Checked before each item is appended
+    validator: Option<Validator<T>>,
+    /// Operation counters, when metrics are enabled
+    metrics: Option<TowerMetrics>,
+    /// Root set by `from_parts` or `recompute_root`, cleared on append
+    cached_root: Option<D::Output>,
+    /// Generated proofs, when proof caching is enabled
+    proof_cache: Option<ProofCache<T, D>>,
+    /// Combined digest of the top level while it holds several nodes
+    top_digest: TopDigestCache<D>,
+    /// Digests of overflowed groups by content, when the memo is enabled
+    group_memo: Option<GroupMemo<D>>,
     /// Phantom data for digest type
     _digest: PhantomData<D>,
 }
@@ -79,27 +456,225 @@ pub struct LazyTower<T, D: Digest> {
 impl<T: Clone + AsRef<[u8]>, D: Digest> LazyTower<T, D> {
     /// Create a new empty LazyTower with the specified width
     pub fn new(width: usize) -> Result<Self, LazyTowerError> {
-        if width <= 1 {
+        Self::with_options(width, TowerOptions::default())
+    }
+
+    /// Create a new empty LazyTower with the specified width and options
+    pub fn with_options(width: usize, options: TowerOptions) -> Result<Self, LazyTowerError> {
+        Self::with_widths_and_options(vec![width], options)
+    }
+
+    /// Create a new empty LazyTower with a width per level
+    ///
+    /// `widths[i]` is the width of level `i`; the last entry applies to every
+    /// level above it. An empty list is rejected as a width of 0.
+    pub fn new_with_widths(widths: Vec<usize>) -> Result<Self, LazyTowerError> {
+        Self::with_widths_and_options(widths, TowerOptions::default())
+    }
+
+    /// Create a new empty LazyTower with a width per level and options
+    pub fn with_widths_and_options(
+        widths: Vec<usize>,
+        options: TowerOptions,
+    ) -> Result<Self, LazyTowerError> {
+        if widths.is_empty() {
+            return Err(LazyTowerError::InvalidWidth { width: 0 });
+        }
+        if let Some(&width) = widths.iter().find(|&&width| width <= 1) {
             return Err(LazyTowerError::InvalidWidth { width });
         }
         Ok(Self {
-            width,
-            levels: vec![Vec::new()],
+            widths,
+            options,
+            overflow_policy: Arc::new(EagerOverflow),
+            levels: Arc::new(vec![Vec::new()]),
             item_count: 0,
-            items: HashMap::new(),
-            item_positions: HashMap::new(),
-            overflow_records: Vec::new(),
-            digest_to_nodes: HashMap::new(),
-            level_nodes: HashMap::new(),
+            items: Arc::default(),
+            metadata: Arc::default(),
+            digest_leaves: Arc::default(),
+            item_digest: None,
+            nonce: None,
+            item_positions: Arc::default(),
+            overflow_records: Arc::default(),
+            digest_to_nodes: Arc::default(),
+            level_nodes: Arc::default(),
+            node_records: Arc::default(),
+            history: None,
+            journal: None,
+            root_collision_hook: None,
+            overflow_hook: None,
+            validator: None,
+            metrics: None,
+            cached_root: None,
+            proof_cache: None,
+            top_digest: TopDigestCache::new(),
+            group_memo: None,
             _digest: PhantomData,
         })
     }
 
+    /// Create a new empty LazyTower whose leaves all commit to `nonce`
+    ///
+    /// Each leaf becomes `digest_items([nonce, item])`, so towers over the
+    /// same items with different nonces have unrelated roots and a proof from
+    /// one never verifies against another. Proofs carry the nonce. Like the
+    /// overflow policy, the nonce is not part of `TowerParts`.
+    pub fn new_with_nonce(width: usize, nonce: Vec<u8>) -> Result<Self, LazyTowerError> {
+        let mut tower = Self::new(width)?;
+        tower.nonce = Some(nonce);
+        Ok(tower)
+    }
+
+    /// Get the nonce mixed into every leaf, if any
+    pub fn nonce(&self) -> Option<&[u8]> {
+        self.nonce.as_deref()
+    }
+
+    /// Create a new empty LazyTower with a custom overflow policy
+    ///
+    /// The policy is not part of `TowerParts`, so towers rebuilt with
+    /// `from_parts` use the default eager policy.
+    pub fn with_overflow_policy<P: OverflowPolicy + 'static>(
+        width: usize,
+        policy: P,
+    ) -> Result<Self, LazyTowerError> {
+        let mut tower = Self::new(width)?;
+        tower.overflow_policy = Arc::new(policy);
+        Ok(tower)
+    }
+
+    /// Build a tower from `items` and iterate proofs for all of them on demand
+    ///
+    /// Avoids materializing every proof up front for huge towers; see
+    /// `LazyProofs` for how shared path segments are reused.
+    pub fn build_with_all_proofs_lazy(
+        width: usize,
+        items: Vec<T>,
+    ) -> Result<LazyProofs<T, D>, LazyTowerError> {
+        let mut tower = Self::new(width)?;
+        for item in items {
+            tower.try_append(item)?;
+        }
+        Ok(LazyProofs::new(tower))
+    }
+
+    /// Rebuild a tower from exported parts
+    ///
+    /// Items are appended in order and `parts.root` becomes the cached root
+    /// without being checked; call `recompute_root` if it may be stale. Leaves
+    /// imported with `from_leaf_digests` are replayed as items.
+    pub fn from_parts(parts: TowerParts<T, D>) -> Result<Self, LazyTowerError> {
+        let mut tower = Self::with_widths_and_options(parts.widths, parts.options)?;
+        for item in parts.items {
+            tower.try_append(item)?;
+        }
+        tower.cached_root = parts.root;
+        tower.invalidate_proof_cache();
+        Ok(tower)
+    }
+
+    /// Build a fresh tower of `new_width` from this tower's items in order
+    ///
+    /// Options, the overflow policy, item metadata and leaf digests carry
+    /// over. The root differs because the structure does, but every item
+    /// stays provable.
+    pub fn rebuild_with_width(&self, new_width: usize) -> Result<Self, LazyTowerError> {
+        let mut tower = Self::with_options(new_width, self.options.clone())?;
+        tower.overflow_policy = Arc::clone(&self.overflow_policy);
+        tower.nonce = self.nonce.clone();
+
+        for index in 0..self.item_count {
+            let item =
+                self.items
+                    .get(&index)
+                    .cloned()
+                    .ok_or(LazyTowerError::InconsistentState {
+                        reason: format!("item {} is missing", index),
+                    })?;
+            tower.append_as_in(self, index, item)?;
+        }
+        Ok(tower)
+    }
+
+    /// Rebuild the tower from the items `keep` accepts, renumbering them
+    ///
+    /// `keep` is called with each item's index in order. Kept items are
+    /// appended again with their metadata, digest leaves as digests, under
+    /// the same widths, options, policy and nonce, so the root changes to commit to the smaller set.
+    /// Hooks and metrics are kept; the proof cache is cleared and any root
+    /// history restarts, since its item counts referred to the old numbering.
+    /// An enabled journal is rewritten to hold the kept items at their new
+    /// indices.
+    /// Returns a map from each kept item's old index to its new one. On error
+    /// the tower is left unchanged.
+    pub fn compact<F>(&mut self, mut keep: F) -> Result<HashMap<usize, usize>, LazyTowerError>
+    where
+        F: FnMut(usize, &T) -> bool,
+    {
+        let mut tower = Self::with_widths_and_options(self.widths.clone(), self.options.clone())?;
+        tower.overflow_policy = Arc::clone(&self.overflow_policy);
+        tower.nonce = self.nonce.clone();
+        tower.journal = self.journal.as_ref().map(|_| Arc::default());
+        tower.group_memo = self.group_memo.clone();
+
+        let mut remap = HashMap::new();
+        for index in 0..self.item_count {
+            let item = self
+                .items
+                .get(&index)
+                .ok_or(LazyTowerError::InconsistentState {
+                    reason: format!("item {} is missing", index),
+                })?;
+            if !keep(index, item) {
+                continue;
+            }
+            remap.insert(index, tower.item_count);
+            tower.append_as_in(self, index, item.clone())?;
+        }
+
+        tower.history = self.history.as_ref().map(|_| Arc::default());
+        tower.root_collision_hook = self.root_collision_hook.take();
+        tower.overflow_hook = self.overflow_hook.take();
+        tower.validator = self.validator.take();
+        tower.metrics = self.metrics;
+        tower.proof_cache = self.proof_cache.take();
+        tower.invalidate_proof_cache();
+        *self = tower;
+        Ok(remap)
+    }
+
+    /// Export the configuration, items and current root of the tower
+    pub fn into_parts(self) -> TowerParts<T, D> {
+        let root = self.root_digest();
+        let mut items = Arc::try_unwrap(self.items).unwrap_or_else(|items| (*items).clone());
+        let items = (0..self.item_count)
+            .filter_map(|index| items.remove(&index))
+            .collect();
+
+        TowerParts {
+            widths: self.widths,
+            options: self.options,
+            items,
+            root,
+        }
+    }
+
     /// Get the current height of the tower (number of levels)
+    ///
+    /// This is the physical height: levels emptied by an overflow are still
+    /// counted. See `logical_height` for the height up to the highest node.
     pub fn height(&self) -> usize {
         self.levels.len()
     }
 
+    /// Get the index of the highest non-empty level plus one (0 when empty)
+    pub fn logical_height(&self) -> usize {
+        self.levels
+            .iter()
+            .rposition(|level| !level.is_empty())
+            .map_or(0, |level| level + 1)
+    }
+
     /// Get the total number of items in the tower
     pub fn len(&self) -> usize {
         self.item_count
@@ -110,243 +685,2222 @@ impl<T: Clone + AsRef<[u8]>, D: Digest> LazyTower<T, D> {
         self.item_count == 0
     }
 
-    /// Get the width of the tower
+    /// Get the width of the tower (the width of level 0)
     pub fn width(&self) -> usize {
-        self.width
+        self.widths[0]
     }
 
-    /// Append an item to the tower (O(1) amortized)
-    pub fn append(&mut self, item: T) {
-        let item_index = self.item_count;
-        self.item_count += 1;
+    /// Collect the tower's diagnostics in one call
+    pub fn stats(&self) -> TowerStats<D> {
+        TowerStats {
+            item_count: self.item_count,
+            live_count: self.levels.iter().map(Vec::len).sum(),
+            height: self.height(),
+            logical_height: self.logical_height(),
+            overflow_count: self.overflow_records.len(),
+            width: self.width(),
+            root: self.root_digest(),
+        }
+    }
 
-        // Store the item for proof generation
-        self.items.insert(item_index, item.clone());
+    /// Check whether this tower still shares its levels, items, journal and
+    /// history with `other`
+    ///
+    /// True for a clone until either tower is mutated.
+    pub fn shares_storage_with(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.levels, &other.levels)
+            && Arc::ptr_eq(&self.items, &other.items)
+            && shared(&self.journal, &other.journal)
+            && shared(&self.history, &other.history)
+    }
 
-        // Track the initial position
-        let position = ItemPosition {
-            level: 0,
-            index: self.levels[0].len(),
-        };
-        self.item_positions.insert(item_index, position.clone());
+    /// Get the configured per-level widths
+    ///
+    /// Uniform towers return a single-element slice.
+    pub fn widths(&self) -> &[usize] {
+        &self.widths
+    }
 
-        // Track the node ID
-        let node_id = NodeId::Item(item_index);
-        self.level_nodes
-            .insert((position.level, position.index), node_id.clone());
+    /// Width of the given level
+    fn width_at(&self, level: usize) -> usize {
+        self.widths[level.min(self.widths.len() - 1)]
+    }
 
-        self.append_to_level(0, TowerNode::Item(item), node_id);
+    /// Number of nodes currently waiting at `level` (0 beyond the top)
+    pub fn nodes_at_level(&self, level: usize) -> usize {
+        self.levels.get(level).map_or(0, Vec::len)
     }
 
-    /// Recursive helper to append a node to a specific level
-    fn append_to_level(&mut self, level: usize, node: TowerNode<T, D>, node_id: NodeId) {
-        // Ensure we have enough levels
-        while self.levels.len() <= level {
-            self.levels.push(Vec::new());
-        }
+    /// Number of nodes `level` can take before it reaches its width
+    ///
+    /// A level that does not exist yet reports its full width. Under a
+    /// policy that defers overflows a level may exceed its width, which
+    /// reports 0.
+    pub fn pending_at_level(&self, level: usize) -> usize {
+        self.width_at(level)
+            .saturating_sub(self.nodes_at_level(level))
+    }
 
-        // Add the node to the current level
-        let node_index = self.levels[level].len();
-        self.levels[level].push(node);
+    /// Check whether the next append would overflow level 0
+    pub fn would_overflow(&self) -> bool {
+        self.overflows(0, self.nodes_at_level(0) + 1, false)
+    }
 
-        // Track node at this position
-        self.level_nodes
-            .insert((level, node_index), node_id.clone());
+    /// Predict the level-0 digest appending `item` would produce
+    ///
+    /// Returns `None` if the append would not overflow level 0. The leaf is
+    /// formed as `try_append` forms it, so the nonce and `hash_leaves` apply.
+    /// The tower is not modified.
+    pub fn peek_next_overflow_digest(&self, item: &T) -> Option<D::Output> {
+        let leaf = item_leaf::<T, D>(self.nonce.as_deref(), self.options.hash_leaves, item);
+        self.overflow_digests(Some(leaf.as_ref()), false)
+            .into_iter()
+            .next()
+    }
 
-        // Check if the level overflows
-        if self.levels[level].len() >= self.width {
-            // Collect node IDs that will be digested
-            let mut overflow_node_ids = Vec::new();
-            for i in 0..self.width {
-                if let Some(nid) = self.level_nodes.get(&(level, i)) {
-                    overflow_node_ids.push(nid.clone());
-                }
-            }
+    /// Get the options the tower was created with
+    pub fn options(&self) -> &TowerOptions {
+        &self.options
+    }
 
-            // Compute digest of the full level
-            let digest = D::digest_items(&self.levels[level]);
-            let digest_bytes = digest.as_ref().to_vec();
+    /// Append an item to the tower (O(1) amortized)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tower options or validator reject the item or the tower
+    /// already holds `usize::MAX` items. Use `try_append` to handle rejection
+    /// instead.
+    pub fn append(&mut self, item: T) {
+        if let Err(err) = self.try_append(item) {
+            panic!("{}", err);
+        }
+    }
 
-            // Create new node ID for the digest
-            let digest_node_id = NodeId::Digest(overflow_node_ids.clone());
+    /// Append an item, returning its index and the number of levels that
+    /// overflowed as a result
+    ///
+    /// The depth is 0 when the item just joins level 0 and reaches the height
+    /// of the tower before the append when every level cascades, exposing the
+    /// occasional appends that pay for the amortized O(1) cost.
+    ///
+    /// # Panics
+    ///
+    /// Panics whenever `append` would.
+    pub fn append_with_cascade(&mut self, item: T) -> (usize, usize) {
+        let index = self.item_count;
+        let first_record = self.overflow_records.len();
+        self.append(item);
 
-            // Track which nodes went into this digest
-            self.digest_to_nodes
-                .insert(digest_bytes.clone(), overflow_node_ids.clone());
+        let levels: BTreeSet<usize> = self.overflow_records[first_record..]
+            .iter()
+            .map(|record| record.level)
+            .collect();
+        (index, levels.len())
+    }
 
-            // Track overflow record
-            self.overflow_records.push(OverflowRecord {
-                level,
-                node_ids: overflow_node_ids,
-                result_digest: digest.clone(),
-            });
+    /// Append every item from `iter`, reporting the root every `flush_every` items
+    ///
+    /// `on_flush` receives the current root after each `flush_every` items
+    /// appended by this call, giving incremental commitments during a long
+    /// stream. A `flush_every` of 0 never flushes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tower options reject an item, as `append` does.
+    pub fn append_stream<I, F>(&mut self, iter: I, flush_every: usize, mut on_flush: F)
+    where
+        I: IntoIterator<Item = T>,
+        F: FnMut(&D::Output),
+    {
+        for (appended, item) in iter.into_iter().enumerate() {
+            self.append(item);
 
-            // Update positions for items at level 0
-            if level == 0 {
-                // Extract item indices from overflow node IDs
-                for node_id in &self.overflow_records.last().unwrap().node_ids {
-                    if let NodeId::Item(idx) = node_id {
-                        if let Some(pos) = self.item_positions.get_mut(idx) {
-                            pos.level = level + 1;
-                            pos.index = self.levels.get(level + 1).map_or(0, |l| l.len());
-                        }
-                    }
+            if flush_every > 0 && (appended + 1) % flush_every == 0 {
+                if let Some(root) = self.root_digest() {
+                    on_flush(&root);
                 }
             }
-
-            // Clear the current level and its node mappings
-            self.levels[level].clear();
-            for i in 0..self.width {
-                self.level_nodes.remove(&(level, i));
-            }
-
-            // Recursively add the digest to the next level
-            self.append_to_level(level + 1, TowerNode::Digest(digest), digest_node_id);
         }
     }
 
-    /// Get a reference to a specific level
-    pub fn level(&self, index: usize) -> Option<&Vec<TowerNode<T, D>>> {
-        self.levels.get(index)
+    /// Append items from a fallible source until it yields an error
+    ///
+    /// Returns the indices of the appended items, or the source's first
+    /// error. Items appended before the error stay in the tower, so callers
+    /// can resume from where the source failed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tower options reject an item, as `append` does.
+    pub fn try_append_all<E>(
+        &mut self,
+        iter: impl Iterator<Item = Result<T, E>>,
+    ) -> Result<Vec<usize>, E> {
+        let mut indices = Vec::new();
+        for item in iter {
+            let item = item?;
+            indices.push(self.item_count);
+            self.append(item);
+        }
+        Ok(indices)
     }
 
-    /// Compute the root digest of the tower
-    pub fn root_digest(&self) -> Option<D::Output> {
-        // Find the highest non-empty level
-        for level in self.levels.iter().rev() {
-            if !level.is_empty() {
-                // If there's only one node at this level, return its digest
-                if level.len() == 1 {
-                    return Some(match &level[0] {
-                        TowerNode::Item(item) => D::digest_item(item),
-                        TowerNode::Digest(digest) => digest.clone(),
-                    });
-                } else {
-                    // Multiple nodes at the top level - compute their combined digest
-                    return Some(D::digest_items(level));
-                }
-            }
+    /// Append an item bound to a metadata value such as a timestamp
+    ///
+    /// The tower commits to the leaf `digest_items([item, meta as 8
+    /// little-endian bytes])` instead of the raw item, and proofs for the item
+    /// carry `meta` so a different claimed value fails verification. Metadata
+    /// is not exported by `into_parts`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tower options reject the item or the tower is full.
+    pub fn append_with_meta(&mut self, item: T, meta: u64) {
+        if let Err(err) = self.check_item(&item) {
+            panic!("{}", err);
+        }
+
+        if let Err(err) = self.append_meta_leaf(item, meta) {
+            panic!("{}", err);
         }
-        None
     }
 
-    /// Generate a proof for an item at a given index
-    pub fn generate_proof(&self, index: usize) -> Result<MembershipProof<T, D>, LazyTowerError> {
-        // Check bounds
-        if self.item_count == 0 || index >= self.item_count {
-            return Err(LazyTowerError::InvalidIndex {
-                index,
-                max: self.item_count,
-            });
+    /// Append the leaf committing to `item` and `meta`, recording the metadata
+    fn append_meta_leaf(&mut self, item: T, meta: u64) -> Result<(), LazyTowerError> {
+        let index = self.item_count;
+        let mut leaf = meta_leaf::<D>(item.as_ref(), meta);
+        if let Some(nonce) = &self.nonce {
+            leaf = nonce_leaf::<D>(nonce, leaf.as_ref());
+        }
+        self.append_leaf(item, TowerNode::Digest(leaf))?;
+        Arc::make_mut(&mut self.metadata).insert(index, meta);
+        if let Some(entry) = self
+            .journal
+            .as_mut()
+            .and_then(|journal| Arc::make_mut(journal).last_mut())
+        {
+            entry.meta = Some(meta);
         }
+        Ok(())
+    }
 
-        // Get the original item
-        let item = self
-            .items
-            .get(&index)
-            .ok_or(LazyTowerError::ProofGenerationNotImplemented)?
-            .clone();
+    /// Get the metadata the item at `index` was appended with
+    pub fn meta(&self, index: usize) -> Option<u64> {
+        self.metadata.get(&index).copied()
+    }
 
-        // Get current root
-        let root = self
-            .root_digest()
-            .ok_or(LazyTowerError::ProofGenerationNotImplemented)?;
+    /// Append an item to the tower, returning an error if the options or
+    /// validator reject it or the item count would overflow `usize`
+    pub fn try_append(&mut self, item: T) -> Result<(), LazyTowerError> {
+        self.check_item(&item)?;
 
-        // Build the proof path
-        let mut path = ProofPath::new();
+        let node = item_leaf(self.nonce.as_deref(), self.options.hash_leaves, &item);
+        self.append_node(item, node)
+    }
 
-        // Simple case: if there's only one item, no siblings needed
-        if self.item_count == 1 {
-            return Ok(MembershipProof { item, path, root });
+    /// Check an item against the options that restrict its bytes and the
+    /// validator
+    fn check_item(&self, item: &T) -> Result<(), LazyTowerError> {
+        let bytes = item.as_ref();
+        if self.options.reject_empty_items && bytes.is_empty() {
+            return Err(LazyTowerError::EmptyItem);
         }
+        if let Some(max) = self.options.max_item_bytes {
+            if bytes.len() > max {
+                return Err(LazyTowerError::ItemTooLarge {
+                    size: bytes.len(),
+                    max,
+                });
+            }
+        }
+        if let Some(Validator(validator)) = &self.validator {
+            validator(item).map_err(|reason| LazyTowerError::ValidationFailed { reason })?;
+        }
+        Ok(())
+    }
 
-        // Build proof path from item to root using NodeId tracking
-        let item_node_id = NodeId::Item(index);
-        self.build_proof_path_recursive(&item_node_id, &mut path)?;
-
-        Ok(MembershipProof { item, path, root })
+    /// Run `validator` on every item before it is appended
+    ///
+    /// An item the validator rejects is not appended and the append fails
+    /// with `LazyTowerError::ValidationFailed` carrying its reason; `append`
+    /// panics with it instead. Replaces any earlier validator. Items already
+    /// in the tower are not checked again, even by `compact`, but edits made
+    /// through `get_mut` are.
+    pub fn set_validator<F>(&mut self, validator: F)
+    where
+        F: Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.validator = Some(Validator(Arc::new(validator)));
     }
 
-    /// Recursively build proof path for a node
-    fn build_proof_path_recursive(
-        &self,
-        node_id: &NodeId,
-        path: &mut ProofPath<D>,
-    ) -> Result<(), LazyTowerError> {
-        // Find which overflow record contains this node
-        for record in &self.overflow_records {
-            if record.node_ids.contains(node_id) {
-                // Find position and siblings within this overflow group
-                let mut position = 0;
-
-                if record.level == 0 {
-                    // Level 0: Use raw siblings (actual item values)
-                    let mut raw_siblings = Vec::new();
-
-                    for (i, nid) in record.node_ids.iter().enumerate() {
-                        if nid == node_id {
-                            position = i;
-                        } else if let NodeId::Item(idx) = nid {
-                            if let Some(item) = self.items.get(idx) {
-                                raw_siblings.push(item.as_ref().to_vec());
-                            }
-                        }
-                    }
+    /// Append every item in `items` or none of them
+    ///
+    /// All items are checked against the tower's options and remaining
+    /// capacity, and every leaf and overflow digest the batch produces is
+    /// checked against `Digest::OUTPUT_LEN`, before the first append, so a
+    /// rejected batch leaves the tower untouched. Returns the index assigned
+    /// to each item, in order.
+    pub fn append_batch_atomic(&mut self, items: Vec<T>) -> Result<Vec<usize>, LazyTowerError> {
+        self.check_batch(&items)?;
+        let leaves: Vec<TowerNode<T, D>> = items
+            .iter()
+            .map(|item| item_leaf(self.nonce.as_deref(), self.options.hash_leaves, item))
+            .collect();
+        let digests = self.batch_overflow_digests(&leaves)?;
 
-                    path.add_raw_siblings(position, raw_siblings);
-                } else {
-                    // Higher levels: Use digest siblings
-                    let mut digest_siblings = Vec::new();
-
-                    for (i, nid) in record.node_ids.iter().enumerate() {
-                        if nid == node_id {
-                            position = i;
-                        } else if let NodeId::Digest(child_nodes) = nid {
-                            // Find the digest for this node group
-                            for overflow_record in &self.overflow_records {
-                                if &overflow_record.node_ids == child_nodes {
-                                    digest_siblings.push(overflow_record.result_digest.clone());
-                                    break;
-                                }
-                            }
-                        }
-                    }
+        let first = self.item_count;
+        for ((item, node), digests) in items.into_iter().zip(leaves).zip(digests) {
+            self.push_node(item, node, digests);
+        }
+        Ok((first..self.item_count).collect())
+    }
 
-                    path.add_siblings(position, digest_siblings);
-                }
+    /// Append every item in `items` or none of them, reporting progress
+    ///
+    /// Checks the batch as `append_batch_atomic` does, then calls
+    /// `on_progress(appended, total)` after every hundredth of the batch, the
+    /// last call always having `appended == total`, so a caller can drive a
+    /// progress bar through a long ingest. An empty or rejected batch never
+    /// calls it.
+    pub fn append_batch_with_progress<F>(
+        &mut self,
+        items: Vec<T>,
+        mut on_progress: F,
+    ) -> Result<Vec<usize>, LazyTowerError>
+    where
+        F: FnMut(usize, usize),
+    {
+        self.check_batch(&items)?;
+        let leaves: Vec<TowerNode<T, D>> = items
+            .iter()
+            .map(|item| item_leaf(self.nonce.as_deref(), self.options.hash_leaves, item))
+            .collect();
+        let digests = self.batch_overflow_digests(&leaves)?;
 
-                // Continue building path for the parent digest
-                let parent_node_id = NodeId::Digest(record.node_ids.clone());
-                return self.build_proof_path_recursive(&parent_node_id, path);
+        let total = items.len();
+        let step = total.div_ceil(100).max(1);
+        let first = self.item_count;
+        let batch = items.into_iter().zip(leaves).zip(digests);
+        for (appended, ((item, node), digests)) in (1..).zip(batch) {
+            self.push_node(item, node, digests);
+            if appended % step == 0 || appended == total {
+                on_progress(appended, total);
             }
         }
+        Ok((first..self.item_count).collect())
+    }
 
-        // If not in any overflow record, check if it's currently at a level
-        for ((level, index), nid) in &self.level_nodes {
-            if nid == node_id {
-                // Found the node at a current level
-                if let Some(level_nodes) = self.levels.get(*level) {
-                    if level_nodes.len() > 1 {
-                        // Has siblings at this level
-                        let mut siblings = Vec::new();
-                        for (i, node) in level_nodes.iter().enumerate() {
-                            if i != *index {
-                                let raw_bytes = match node {
-                                    TowerNode::Item(item) => item.as_ref().to_vec(),
-                                    TowerNode::Digest(d) => d.as_ref().to_vec(),
-                                };
-                                siblings.push(raw_bytes);
-                            }
-                        }
-                        path.add_raw_siblings(*index, siblings);
-                    }
-                }
-                return Ok(());
-            }
-        }
+    /// Append every item in `items` or none of them, computing leaves in parallel
+    ///
+    /// Behaves like `append_batch_atomic`, but the per-item leaf digests of
+    /// towers with a nonce or `hash_leaves` are computed on the rayon pool
+    /// before the overflows are applied in order. The root and proofs match
+    /// a serial build.
+    #[cfg(feature = "rayon")]
+    pub fn append_batch_par(&mut self, items: Vec<T>) -> Result<Vec<usize>, LazyTowerError>
+    where
+        T: Send + Sync,
+        D::Output: Send,
+    {
+        use rayon::prelude::*;
 
-        Ok(())
-    }
+        self.check_batch(&items)?;
+
+        let nonce = self.nonce.as_deref();
+        let hash_leaves = self.options.hash_leaves;
+        let nodes: Vec<TowerNode<T, D>> = items
+            .par_iter()
+            .map(|item| item_leaf(nonce, hash_leaves, item))
+            .collect();
+        let digests = self.batch_overflow_digests(&nodes)?;
+
+        let first = self.item_count;
+        for ((item, node), digests) in items.into_iter().zip(nodes).zip(digests) {
+            self.push_node(item, node, digests);
+        }
+        Ok((first..self.item_count).collect())
+    }
+
+    /// Check every item of a batch against the options and validator, and
+    /// the batch against the remaining capacity
+    fn check_batch(&self, items: &[T]) -> Result<(), LazyTowerError> {
+        for item in items {
+            self.check_item(item)?;
+        }
+        if usize::MAX - self.item_count < items.len() {
+            return Err(LazyTowerError::CapacityExceeded);
+        }
+        Ok(())
+    }
+
+    /// Compute the overflow digests each leaf of a batch will cause when
+    /// appended in order
+    ///
+    /// Replays the eager cascade of `cascade` over the bytes of the current
+    /// levels without touching them, checking every leaf and overflow digest
+    /// against `Digest::OUTPUT_LEN`, so a batch that would fail part way is
+    /// rejected before its first append.
+    fn batch_overflow_digests(
+        &self,
+        leaves: &[TowerNode<T, D>],
+    ) -> Result<Vec<Vec<D::Output>>, LazyTowerError> {
+        let mut levels: Vec<Vec<Cow<'_, [u8]>>> = self
+            .levels
+            .iter()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .map(|node| Cow::Borrowed(node.as_ref()))
+                    .collect()
+            })
+            .collect();
+
+        let mut batch = Vec::with_capacity(leaves.len());
+        for leaf in leaves {
+            if let TowerNode::Digest(digest) = leaf {
+                check_digest_len::<D>(digest)?;
+            }
+            levels[0].push(Cow::Borrowed(leaf.as_ref()));
+
+            let mut digests = Vec::new();
+            let mut level = 0;
+            while level < levels.len() {
+                let mut collapsed = false;
+                while self.overflows(level, levels[level].len(), false) {
+                    let width = self.width_at(level);
+                    let group: Vec<&[u8]> = levels[level][..width]
+                        .iter()
+                        .map(|node| node.as_ref())
+                        .collect();
+                    let digest = self.group_digest(level, &group);
+                    check_digest_len::<D>(&digest)?;
+
+                    levels[level].drain(..width);
+                    if levels.len() == level + 1 {
+                        levels.push(Vec::new());
+                    }
+                    levels[level + 1].push(Cow::Owned(digest.as_ref().to_vec()));
+                    digests.push(digest);
+                    collapsed = true;
+                }
+
+                if !collapsed {
+                    break;
+                }
+                level += 1;
+            }
+            batch.push(digests);
+        }
+        Ok(batch)
+    }
+
+    /// Append `item` as the leaf digest `item_digest` reads it as, recording
+    /// it so the leaf is rebuilt as a digest
+    fn append_digest_leaf(
+        &mut self,
+        item: T,
+        item_digest: ItemDigestFn<T, D>,
+    ) -> Result<(), LazyTowerError> {
+        let index = self.item_count;
+        let Some(digest) = item_digest(&item) else {
+            return Err(LazyTowerError::InconsistentState {
+                reason: format!("item {} is not a digest", index),
+            });
+        };
+        self.append_leaf(item, self.digest_leaf(&digest))?;
+        Arc::make_mut(&mut self.digest_leaves).insert(index);
+        self.item_digest = Some(item_digest);
+        if let Some(entry) = self
+            .journal
+            .as_mut()
+            .and_then(|journal| Arc::make_mut(journal).last_mut())
+        {
+            entry.digest = true;
+        }
+        Ok(())
+    }
+
+    /// How the item at `index` is read back as its leaf digest, if it was
+    /// appended as one
+    fn item_digest_at(&self, index: usize) -> Option<ItemDigestFn<T, D>> {
+        self.item_digest
+            .filter(|_| self.digest_leaves.contains(&index))
+    }
+
+    /// Append `item` the way `source` committed its item at `index`: bound to
+    /// its metadata, as a leaf digest, or as a plain item
+    fn append_as_in(&mut self, source: &Self, index: usize, item: T) -> Result<(), LazyTowerError> {
+        match (source.meta(index), source.item_digest_at(index)) {
+            (Some(meta), _) => self.append_meta_leaf(item, meta),
+            (None, Some(item_digest)) => self.append_digest_leaf(item, item_digest),
+            (None, None) => self.try_append(item),
+        }
+    }
+
+    /// Level-0 node for a precomputed leaf digest before `hash_leaves`
+    fn digest_leaf(&self, digest: &D::Output) -> TowerNode<T, D> {
+        TowerNode::Digest(match &self.nonce {
+            Some(nonce) => nonce_leaf::<D>(nonce, digest.as_ref()),
+            None => digest.clone(),
+        })
+    }
+
+    /// Append a leaf node at level 0, keeping `item` for proof generation
+    fn append_leaf(&mut self, item: T, node: TowerNode<T, D>) -> Result<(), LazyTowerError> {
+        let node = if self.options.hash_leaves {
+            TowerNode::Digest(D::digest_item(&node))
+        } else {
+            node
+        };
+        self.append_node(item, node)
+    }
+
+    /// Append a node that already has `hash_leaves` applied
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "append", level = "debug", skip_all, fields(index = self.item_count))
+    )]
+    fn append_node(&mut self, item: T, node: TowerNode<T, D>) -> Result<(), LazyTowerError> {
+        if self.item_count == usize::MAX {
+            return Err(LazyTowerError::CapacityExceeded);
+        }
+
+        // Compute every overflow digest before mutating so a panicking or
+        // misbehaving digest leaves the tower untouched
+        let digests = self.overflow_digests(Some(node.as_ref()), false);
+        if let TowerNode::Digest(leaf) = &node {
+            check_digest_len::<D>(leaf)?;
+        }
+        for digest in &digests {
+            check_digest_len::<D>(digest)?;
+        }
+        self.push_node(item, node, digests);
+        Ok(())
+    }
+
+    /// Push a checked leaf onto level 0 and cascade with its precomputed
+    /// overflow digests
+    fn push_node(&mut self, item: T, node: TowerNode<T, D>, digests: Vec<D::Output>) {
+        if let Some(metrics) = self.metrics.as_mut() {
+            metrics.total_appends += 1;
+            metrics.total_digest_items_calls += digests.len() as u64;
+        }
+        let mut digests = digests.into_iter();
+
+        // Any cached root, top digest or proof is stale once the item is added
+        self.invalidate_caches();
+
+        let item_index = self.item_count;
+        self.item_count += 1;
+
+        // Store the item for proof generation
+        if let Some(journal) = self.journal.as_mut() {
+            Arc::make_mut(journal).push(JournalEntry {
+                index: item_index,
+                bytes: item.as_ref().to_vec(),
+                meta: None,
+                digest: false,
+            });
+        }
+        Arc::make_mut(&mut self.items).insert(item_index, item);
+
+        // Track the initial position
+        let position = ItemPosition {
+            level: 0,
+            index: self.levels[0].len(),
+        };
+        Arc::make_mut(&mut self.item_positions).insert(item_index, position.clone());
+
+        // Track the node ID
+        let node_id = NodeId::Item(item_index);
+        Arc::make_mut(&mut self.level_nodes).insert((position.level, position.index), node_id);
+        Arc::make_mut(&mut self.levels)[0].push(node);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(level = 0, position = position.index, "item appended");
+
+        let first_record = self.overflow_records.len();
+        self.cascade(false, &mut digests);
+
+        // Record the new root if history is being tracked
+        if self.history.is_some() {
+            self.record_history();
+        }
+
+        self.notify_overflows(first_record);
+    }
+
+    /// Start counting appends, overflows and digest calls
+    pub fn enable_metrics(&mut self) {
+        if self.metrics.is_none() {
+            self.metrics = Some(TowerMetrics::default());
+        }
+    }
+
+    /// Get the accumulated counters, if metrics are enabled
+    pub fn metrics(&self) -> Option<&TowerMetrics> {
+        self.metrics.as_ref()
+    }
+
+    /// Start recording every subsequent append in the journal
+    pub fn enable_journal(&mut self) {
+        if self.journal.is_none() {
+            self.journal = Some(Arc::default());
+        }
+    }
+
+    /// Get the appends recorded since the journal was enabled
+    ///
+    /// Empty when the journal is disabled.
+    pub fn journal(&self) -> &[JournalEntry] {
+        self.journal.as_deref().map_or(&[], Vec::as_slice)
+    }
+
+    /// Start recording the root after every subsequent append
+    pub fn enable_history(&mut self) {
+        if self.history.is_none() {
+            self.history = Some(Arc::default());
+        }
+    }
+
+    /// Call `hook` whenever an append produces a root already in history
+    ///
+    /// The root only covers the top level, so it legitimately repeats while
+    /// appends land below it. A repeat counts as a collision only when the
+    /// earlier root covered a different number of items, meaning two
+    /// distinct item sequences share a root. The hook receives the root, the item
+    /// count it was first recorded at and the current item count. Enables
+    /// history tracking; each check is a lookup in an index of past roots.
+    pub fn on_root_collision<F>(&mut self, hook: F)
+    where
+        F: Fn(&D::Output, usize, usize) + Send + Sync + 'static,
+    {
+        self.enable_history();
+        self.root_collision_hook = Some(RootCollisionHook(Arc::new(hook)));
+    }
+
+    /// Push the current root onto the history, reporting any collision
+    fn record_history(&mut self) {
+        let (Some(root), Some(covered)) = (self.root_digest(), self.covered_items()) else {
+            return;
+        };
+        let Some(history) = self.history.as_mut() else {
+            return;
+        };
+
+        if let Some(hook) = &self.root_collision_hook {
+            if let Some(earlier) = history.collision(&root, covered) {
+                (hook.0)(&root, earlier, self.item_count);
+            }
+        }
+
+        Arc::make_mut(history).push(HistoryEntry {
+            item_count: self.item_count,
+            root,
+            covered,
+        });
+    }
+
+    /// Number of leading items under the top level, which the root covers
+    ///
+    /// Lower levels only ever hold later items, so the last item beneath the
+    /// top level's last node bounds the covered prefix.
+    fn covered_items(&self) -> Option<usize> {
+        let (level, nodes) = self
+            .levels
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, nodes)| !nodes.is_empty())?;
+        let mut node_id = self.level_nodes.get(&(level, nodes.len() - 1))?;
+        loop {
+            match node_id {
+                NodeId::Item(index) => return Some(index + 1),
+                NodeId::Digest(record) => {
+                    node_id = self.overflow_records.get(*record)?.node_ids.last()?;
+                }
+            }
+        }
+    }
+
+    /// Check if history tracking is enabled
+    pub fn is_history_enabled(&self) -> bool {
+        self.history.is_some()
+    }
+
+    /// Iterate over recorded (item_count, root) pairs from oldest to newest
+    ///
+    /// Yields nothing when history tracking is disabled.
+    pub fn history(&self) -> impl Iterator<Item = (usize, &D::Output)> {
+        self.history
+            .iter()
+            .flat_map(|history| history.entries.iter())
+            .map(|entry| (entry.item_count, &entry.root))
+    }
+
+    /// Drop the oldest history entries, keeping at most `keep` of the newest
+    pub fn shrink_history(&mut self, keep: usize) {
+        if let Some(history) = self.history.as_mut() {
+            if history.entries.len() > keep {
+                Arc::make_mut(history).truncate_front(keep);
+            }
+        }
+    }
+
+    /// Collapse every level into a single top node, ignoring the policy
+    ///
+    /// Working bottom-up, full groups are digested first, completing the
+    /// overflows deferred by a policy such as `LazyOverflow`. Any nodes left
+    /// below the top are then digested as a short group, so the root commits
+    /// to every item and every item has a verifiable proof. Short groups are
+    /// not padded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a digest's length differs from `Digest::OUTPUT_LEN`; see
+    /// `try_flush`.
+    pub fn flush(&mut self) {
+        if let Err(err) = self.try_flush() {
+            panic!("{}", err);
+        }
+    }
+
+    /// Collapse every level as `flush` does, returning an error if a digest's
+    /// length differs from `Digest::OUTPUT_LEN`
+    ///
+    /// Every digest is checked before any is stored, so on error the tower
+    /// is unchanged.
+    pub fn try_flush(&mut self) -> Result<(), LazyTowerError> {
+        let digests = self.overflow_digests(None, true);
+        if digests.is_empty() {
+            return Ok(());
+        }
+        for digest in &digests {
+            check_digest_len::<D>(digest)?;
+        }
+        if let Some(metrics) = self.metrics.as_mut() {
+            metrics.total_digest_items_calls += digests.len() as u64;
+        }
+
+        self.invalidate_caches();
+
+        let first_record = self.overflow_records.len();
+        self.cascade(true, &mut digests.into_iter());
+        self.notify_overflows(first_record);
+        Ok(())
+    }
+
+    /// Call `hook` for every overflow caused by an append or flush
+    ///
+    /// A single append may cascade through several levels, creating new ones
+    /// on the way. The hook runs only after the whole cascade has finished,
+    /// once per overflow from the bottom up, so `level` and `root_digest`
+    /// reads inside it always see the final structure rather than a level
+    /// caught mid-collapse. It receives the tower, the level that overflowed
+    /// and the digest pushed to the level above.
+    pub fn on_overflow<F>(&mut self, hook: F)
+    where
+        F: Fn(&LazyTower<T, D>, usize, &D::Output) + Send + Sync + 'static,
+    {
+        self.overflow_hook = Some(OverflowHook(Arc::new(hook)));
+    }
+
+    /// Report the overflow records created since `first_record` to the hook
+    fn notify_overflows(&self, first_record: usize) {
+        if let Some(hook) = &self.overflow_hook {
+            for record in &self.overflow_records[first_record..] {
+                (hook.0)(self, record.level, &record.result_digest);
+            }
+        }
+    }
+
+    /// Whether `level` must collapse its first `width` nodes now
+    fn overflows(&self, level: usize, len: usize, force: bool) -> bool {
+        let width = self.width_at(level);
+        len >= width && (force || self.overflow_policy.should_overflow(level, len, width))
+    }
+
+    /// Whether a flush must collapse the `remaining` nodes of a level as a short group
+    ///
+    /// A lone node stays put only when nothing sits above it.
+    fn flushes_partial(&self, level: usize, remaining: usize, carried_up: bool) -> bool {
+        let above = carried_up
+            || self
+                .levels
+                .iter()
+                .skip(level + 1)
+                .any(|nodes| !nodes.is_empty());
+        remaining > 1 || (remaining == 1 && above)
+    }
+
+    /// Compute the digests `cascade` will produce, in the order it consumes them
+    ///
+    /// `incoming` is the bytes of a leaf about to be pushed onto level 0.
+    fn overflow_digests(&self, incoming: Option<&[u8]>, force: bool) -> Vec<D::Output> {
+        // Nodes pushed onto the current level by the level below: `None` is
+        // the incoming leaf, `Some(i)` is `digests[i]`
+        let mut carried: Vec<Option<usize>> = incoming.map(|_| None).into_iter().collect();
+        let mut digests: Vec<D::Output> = Vec::new();
+        let mut level = 0;
+
+        while !carried.is_empty() || (force && level < self.levels.len()) {
+            let existing = self.levels.get(level).map_or(&[][..], |l| l.as_slice());
+            let width = self.width_at(level);
+            let total = existing.len() + carried.len();
+
+            let mut next = Vec::new();
+            let mut start = 0;
+            loop {
+                let remaining = total - start;
+                let count = if self.overflows(level, remaining, force) {
+                    width
+                } else if force && self.flushes_partial(level, remaining, !next.is_empty()) {
+                    remaining
+                } else {
+                    break;
+                };
+
+                let digest = {
+                    let group: Vec<&[u8]> = (start..start + count)
+                        .map(|i| match existing.get(i) {
+                            Some(node) => node.as_ref(),
+                            None => match carried[i - existing.len()] {
+                                Some(d) => digests[d].as_ref(),
+                                None => incoming.unwrap_or_default(),
+                            },
+                        })
+                        .collect();
+                    self.group_digest(level, &group)
+                };
+                next.push(Some(digests.len()));
+                digests.push(digest);
+                start += count;
+            }
+
+            carried = next;
+            level += 1;
+        }
+
+        digests
+    }
+
+    /// Digest of a group overflowing from `level`, through the memo if enabled
+    fn group_digest(&self, level: usize, group: &[&[u8]]) -> D::Output {
+        match &self.group_memo {
+            Some(memo) => memo.digest(level, group),
+            None => digest_group::<D, _>(level, group),
+        }
+    }
+
+    /// Collapse full levels bottom-up, taking each digest from `digests`
+    fn cascade(&mut self, force: bool, digests: &mut impl Iterator<Item = D::Output>) {
+        let mut level = 0;
+        while level < self.levels.len() {
+            let mut collapsed = false;
+            loop {
+                let remaining = self.levels[level].len();
+                let count = if self.overflows(level, remaining, force) {
+                    self.width_at(level)
+                } else if force && self.flushes_partial(level, remaining, false) {
+                    remaining
+                } else {
+                    break;
+                };
+
+                let digest = digests
+                    .next()
+                    .expect("overflow digest computed for every collapsed group");
+                self.collapse_front(level, count, digest);
+                collapsed = true;
+            }
+
+            if !collapsed && !force {
+                return;
+            }
+            level += 1;
+        }
+    }
+
+    /// Replace the first `count` nodes of `level` with `digest` on the level above
+    fn collapse_front(&mut self, level: usize, count: usize, digest: D::Output) {
+        let len = self.levels[level].len();
+
+        // Collect node IDs that will be digested
+        let overflow_node_ids: Vec<NodeId> = (0..count)
+            .filter_map(|i| self.level_nodes.get(&(level, i)).cloned())
+            .collect();
+
+        if let Some(metrics) = self.metrics.as_mut() {
+            metrics.total_overflows += 1;
+        }
+
+        // Create new node ID for the digest, keyed by the record created below
+        let record_index = self.overflow_records.len();
+        let digest_node_id = NodeId::Digest(record_index);
+
+        // Track which nodes went into this digest
+        Arc::make_mut(&mut self.digest_to_nodes)
+            .insert(digest_node_id.clone(), overflow_node_ids.clone());
+
+        // Index the record by its members for proof lookups
+        for nid in &overflow_node_ids {
+            Arc::make_mut(&mut self.node_records).insert(nid.clone(), record_index);
+        }
+
+        // Ensure the parent level exists
+        if self.levels.len() <= level + 1 {
+            Arc::make_mut(&mut self.levels).push(Vec::new());
+        }
+        let parent_index = self.levels[level + 1].len();
+
+        // Update positions for items at level 0
+        if level == 0 {
+            for node_id in &overflow_node_ids {
+                if let NodeId::Item(idx) = node_id {
+                    if let Some(pos) = Arc::make_mut(&mut self.item_positions).get_mut(idx) {
+                        pos.level = level + 1;
+                        pos.index = parent_index;
+                    }
+                }
+            }
+        }
+
+        // Track overflow record
+        Arc::make_mut(&mut self.overflow_records).push(OverflowRecord {
+            level,
+            node_ids: overflow_node_ids,
+            result_digest: digest.clone(),
+        });
+
+        // Remove the group and shift any remaining nodes to the front
+        Arc::make_mut(&mut self.levels)[level].drain(..count);
+        for i in 0..count {
+            Arc::make_mut(&mut self.level_nodes).remove(&(level, i));
+        }
+        for i in count..len {
+            if let Some(nid) = Arc::make_mut(&mut self.level_nodes).remove(&(level, i)) {
+                if let NodeId::Item(idx) = &nid {
+                    if let Some(pos) = Arc::make_mut(&mut self.item_positions).get_mut(idx) {
+                        pos.index = i - count;
+                    }
+                }
+                Arc::make_mut(&mut self.level_nodes).insert((level, i - count), nid);
+            }
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(level, count, parent_index, "level overflowed");
+
+        // Add the digest to the parent level
+        Arc::make_mut(&mut self.levels)[level + 1].push(TowerNode::Digest(digest));
+        Arc::make_mut(&mut self.level_nodes).insert((level + 1, parent_index), digest_node_id);
+    }
+
+    /// Get a reference to a specific level
+    ///
+    /// Levels are only observable between operations; hooks set with
+    /// `on_overflow` run after the cascade that fired them has finished.
+    pub fn level(&self, index: usize) -> Option<&Vec<TowerNode<T, D>>> {
+        self.levels.get(index)
+    }
+
+    /// Get mutable access to a specific level
+    ///
+    /// For constructing invalid states in tests. Nothing here keeps node
+    /// tracking, positions or the cached root in step with the edit, so the
+    /// tower's invariants are the caller's responsibility; never use this in
+    /// production code.
+    #[cfg(feature = "debug-internals")]
+    pub fn level_mut(&mut self, index: usize) -> Option<&mut Vec<TowerNode<T, D>>> {
+        Arc::make_mut(&mut self.levels).get_mut(index)
+    }
+
+    /// Overwrite the number of appended items
+    ///
+    /// For exercising capacity limits without appending that many items. The
+    /// stored items and levels no longer match the count afterwards.
+    #[cfg(feature = "debug-internals")]
+    pub fn set_item_count(&mut self, count: usize) {
+        self.item_count = count;
+    }
+
+    /// Drop the cached root, top-level digest and any cached proofs
+    ///
+    /// Lets tests observe the effect of `level_mut` edits on `root_digest`.
+    #[cfg(feature = "debug-internals")]
+    pub fn invalidate_root_cache(&mut self) {
+        self.invalidate_caches();
+    }
+
+    /// Compute the root digest of the tower
+    ///
+    /// A top level holding one node is never combined as a group: a lone
+    /// item gives `D::digest_item(item)` and a lone digest is the root
+    /// itself, whatever the width. A tower of one item therefore has the
+    /// root `D::digest_item(item)`, with or without `hash_leaves`, unless a
+    /// nonce or metadata gave it a different leaf. Its proof has an empty
+    /// path.
+    ///
+    /// Returns the cached root when one is set by `from_parts` or
+    /// `recompute_root`; appending clears the cache. A top level of several
+    /// nodes is combined once and reused until the levels next change.
+    pub fn root_digest(&self) -> Option<D::Output> {
+        if let Some(root) = &self.cached_root {
+            return Some(root.clone());
+        }
+        self.compute_root()
+    }
+
+    /// Rebuild the root from the current levels and update the cache
+    ///
+    /// This is the recovery path when an imported cached root may be stale.
+    pub fn recompute_root(&mut self) -> Option<D::Output> {
+        self.top_digest.clear();
+        self.cached_root = self.compute_root();
+        self.invalidate_proof_cache();
+        self.cached_root.clone()
+    }
+
+    /// Compute the root digest from the current levels
+    fn compute_root(&self) -> Option<D::Output> {
+        // Find the highest non-empty level
+        for (height, level) in self.levels.iter().enumerate().rev() {
+            if !level.is_empty() {
+                // If there's only one node at this level, return its digest
+                if level.len() == 1 {
+                    return Some(match &level[0] {
+                        TowerNode::Item(item) => D::digest_item(item),
+                        TowerNode::Digest(digest) => digest.clone(),
+                    });
+                } else {
+                    // Multiple nodes at the top level - combine them once per change
+                    return Some(
+                        self.top_digest
+                            .get_or_compute(|| digest_group::<D, _>(height, level)),
+                    );
+                }
+            }
+        }
+        None
+    }
+
+    /// Check that the root commits to exactly the given item sequence
+    ///
+    /// Recomputes the expected root from `items` with a plain level-by-level
+    /// fold that shares no state with the tower, then compares it to
+    /// `root_digest()`. Intended for full audits; costs O(n) digest work.
+    /// Items appended with `append_with_meta` are bound to their recorded
+    /// metadata, as the tower committed them.
+    ///
+    /// The root only covers the top non-empty level, so the item count is
+    /// compared as well to reject sequences that differ below it.
+    /// The fold follows the default eager overflow policy and does not model
+    /// the short groups digested by `flush`.
+    pub fn verify_root_against_items(&self, items: &[T]) -> bool {
+        if items.len() != self.item_count {
+            return false;
+        }
+
+        let mut levels: Vec<Vec<Vec<u8>>> = Vec::new();
+
+        for (index, item) in items.iter().enumerate() {
+            let mut node = match self.metadata.get(&index) {
+                Some(&meta) => meta_leaf::<D>(item.as_ref(), meta).as_ref().to_vec(),
+                None => item.as_ref().to_vec(),
+            };
+            if let Some(nonce) = &self.nonce {
+                node = nonce_leaf::<D>(nonce, &node).as_ref().to_vec();
+            }
+            if self.options.hash_leaves {
+                node = D::digest_item(&node).as_ref().to_vec();
+            }
+            let mut level = 0;
+            loop {
+                if levels.len() <= level {
+                    levels.push(Vec::new());
+                }
+                levels[level].push(node);
+                if levels[level].len() < self.width_at(level) {
+                    break;
+                }
+                node = digest_group::<D, _>(level, &levels[level])
+                    .as_ref()
+                    .to_vec();
+                levels[level].clear();
+                level += 1;
+            }
+        }
+
+        // Level 0 holds raw items unless they were hashed, nonced, bound to
+        // metadata or appended as digests; a lone level-0 node at the top is
+        // the last item, and a lone digest leaf is the root as it is
+        let raw_leaves = !self.options.hash_leaves
+            && self.nonce.is_none()
+            && !items.len().checked_sub(1).is_some_and(|last| {
+                self.metadata.contains_key(&last) || self.digest_leaves.contains(&last)
+            });
+        let expected = levels
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, nodes)| !nodes.is_empty())
+            .map(|(level, nodes)| match nodes.as_slice() {
+                [item] if level == 0 && raw_leaves => D::digest_item(item).as_ref().to_vec(),
+                [digest] => digest.clone(),
+                _ => digest_group::<D, _>(level, nodes).as_ref().to_vec(),
+            });
+
+        expected.as_deref() == self.root_digest().as_ref().map(|root| root.as_ref())
+    }
+
+    /// Check that the levels, node tracking and item count agree with each other
+    pub fn validate(&self) -> Result<(), LazyTowerError> {
+        let inconsistent = |reason: String| Err(LazyTowerError::InconsistentState { reason });
+
+        if self.items.len() != self.item_count || self.item_positions.len() != self.item_count {
+            return inconsistent(format!(
+                "{} items stored and {} positions tracked for {} appended items",
+                self.items.len(),
+                self.item_positions.len(),
+                self.item_count
+            ));
+        }
+
+        // Number of items each overflow record stands for; flushed groups
+        // may be shorter than the level width
+        let mut record_items: Vec<usize> = Vec::with_capacity(self.overflow_records.len());
+        for (index, record) in self.overflow_records.iter().enumerate() {
+            let mut count = 0;
+            for node_id in &record.node_ids {
+                count += match node_id {
+                    NodeId::Item(_) => 1,
+                    NodeId::Digest(child) if *child < index => record_items[*child],
+                    NodeId::Digest(child) => {
+                        return inconsistent(format!(
+                            "overflow record {} refers to later record {}",
+                            index, child
+                        ))
+                    }
+                };
+            }
+            record_items.push(count);
+        }
+
+        let mut tracked_nodes = 0;
+        let mut represented_items = 0;
+        for (level, nodes) in self.levels.iter().enumerate() {
+            let width = self.width_at(level);
+            if self.overflows(level, nodes.len(), false) {
+                return inconsistent(format!(
+                    "level {} holds {} nodes but width is {}",
+                    level,
+                    nodes.len(),
+                    width
+                ));
+            }
+
+            for index in 0..nodes.len() {
+                represented_items += match self.level_nodes.get(&(level, index)) {
+                    Some(NodeId::Item(_)) => 1,
+                    Some(NodeId::Digest(record)) => record_items.get(*record).copied().unwrap_or(0),
+                    None => {
+                        return inconsistent(format!(
+                            "node {} at level {} is untracked",
+                            index, level
+                        ))
+                    }
+                };
+            }
+            tracked_nodes += nodes.len();
+        }
+
+        if tracked_nodes != self.level_nodes.len() {
+            return inconsistent(format!(
+                "{} nodes tracked for {} nodes in levels",
+                self.level_nodes.len(),
+                tracked_nodes
+            ));
+        }
+
+        if represented_items != self.item_count {
+            return inconsistent(format!(
+                "levels represent {} items but {} were appended",
+                represented_items, self.item_count
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Capture the tower structure without copies of its items
+    pub fn skeleton(&self) -> TowerSkeleton<D> {
+        // Level 0 holds the most recently appended items
+        let first_pending = self.item_count - self.levels[0].len();
+
+        let levels = self
+            .levels
+            .iter()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .enumerate()
+                    .map(|(index, node)| match node {
+                        TowerNode::Item(_) => SkeletonNode::Item(first_pending + index),
+                        TowerNode::Digest(digest) => SkeletonNode::Digest(digest.clone()),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        TowerSkeleton {
+            width: self.width(),
+            item_count: self.item_count,
+            levels,
+            root: self.root_digest(),
+            overflow_records: (*self.overflow_records).clone(),
+            node_records: (*self.node_records).clone(),
+            level_nodes: (*self.level_nodes).clone(),
+            hash_leaves: self.options.hash_leaves,
+            nonce: self.nonce.clone(),
+            metadata: (*self.metadata).clone(),
+        }
+    }
+
+    /// Get the item appended at `index`
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.items.get(&index)
+    }
+
+    /// Get mutable access to the item at `index`
+    ///
+    /// The guard holds a copy of the item. When it is dropped or committed
+    /// the copy is checked against the tower options and validator, stored
+    /// back, and the item's leaf, every digest above it and the root are
+    /// recomputed, whatever part of the item was changed. If the copy is
+    /// rejected or a recomputed digest's length differs from
+    /// `Digest::OUTPUT_LEN`, the edit is discarded and the item kept as it
+    /// was; `ItemGuard::commit` reports why. Leaves appended with
+    /// `append_digest` are rebuilt as digests. Journals record appends only, so
+    /// a replay reproduces the item as it was appended.
+    pub fn get_mut(&mut self, index: usize) -> Option<ItemGuard<'_, T, D>> {
+        let item = self.items.get(&index)?.clone();
+        Some(ItemGuard {
+            tower: self,
+            index,
+            item: Some(item),
+        })
+    }
+
+    /// Level-0 node the item at `index` is committed as
+    ///
+    /// Leaves appended with `append_digest` are rebuilt as digests, so a lone
+    /// one stays its own root.
+    fn leaf_node(&self, index: usize) -> Option<TowerNode<T, D>> {
+        let item = self.items.get(&index)?;
+        let digest = self
+            .item_digest_at(index)
+            .and_then(|item_digest| item_digest(item));
+        let node = match (self.metadata.get(&index), digest) {
+            (Some(&meta), _) => {
+                let mut leaf = meta_leaf::<D>(item.as_ref(), meta);
+                if let Some(nonce) = &self.nonce {
+                    leaf = nonce_leaf::<D>(nonce, leaf.as_ref());
+                }
+                TowerNode::Digest(leaf)
+            }
+            (None, Some(digest)) => self.digest_leaf(&digest),
+            (None, None) => item_leaf::<T, D>(self.nonce.as_deref(), false, item),
+        };
+        Some(if self.options.hash_leaves {
+            TowerNode::Digest(D::digest_item(&node))
+        } else {
+            node
+        })
+    }
+
+    /// Rebuild the leaf of the item at `index` and every digest above it
+    ///
+    /// Every digest is computed and checked against `Digest::OUTPUT_LEN`
+    /// before any is stored, so on error the tower is unchanged.
+    fn refresh_item(&mut self, index: usize) -> Result<(), LazyTowerError> {
+        let Some(mut node) = self.leaf_node(index) else {
+            return Ok(());
+        };
+        if let TowerNode::Digest(leaf) = &node {
+            check_digest_len::<D>(leaf)?;
+        }
+
+        let mut node_id = NodeId::Item(index);
+        let mut refreshed = Vec::new();
+        while let Some(&record_index) = self.node_records.get(&node_id) {
+            let record = &self.overflow_records[record_index];
+            let group: Vec<Vec<u8>> = record
+                .node_ids
+                .iter()
+                .map(|nid| match nid {
+                    // The node on the path has already been rebuilt
+                    _ if *nid == node_id => node.as_ref().to_vec(),
+                    NodeId::Item(i) if self.options.hash_leaves => self
+                        .leaf_digest(*i)
+                        .map(|digest| digest.as_ref().to_vec())
+                        .unwrap_or_default(),
+                    NodeId::Item(i) => self.item_bytes(*i).unwrap_or_default(),
+                    NodeId::Digest(child) => self.overflow_records[*child]
+                        .result_digest
+                        .as_ref()
+                        .to_vec(),
+                })
+                .collect();
+            let digest = digest_group::<D, _>(record.level, &group);
+            check_digest_len::<D>(&digest)?;
+
+            refreshed.push((record_index, digest.clone()));
+            node_id = NodeId::Digest(record_index);
+            node = TowerNode::Digest(digest);
+        }
+
+        self.invalidate_caches();
+        if !refreshed.is_empty() {
+            let records = Arc::make_mut(&mut self.overflow_records);
+            for (record_index, digest) in refreshed {
+                records[record_index].result_digest = digest;
+            }
+        }
+
+        // The highest refreshed node sits at a current level
+        let place = self
+            .level_nodes
+            .iter()
+            .find(|(_, nid)| **nid == node_id)
+            .map(|(&place, _)| place);
+        if let Some((level, position)) = place {
+            Arc::make_mut(&mut self.levels)[level][position] = node;
+        }
+        Ok(())
+    }
+
+    /// Get `digest_item` of every stored item's leaf in index order
+    ///
+    /// Each leaf is the item as the tower commits to it, with its metadata
+    /// and the tower's nonce applied, so for towers with `hash_leaves` these
+    /// are the level-0 nodes. This is the canonical leaf layer for systems
+    /// that expect one; it is hashed with `Digest::digest_batch`.
+    pub fn leaf_digests(&self) -> Vec<D::Output> {
+        let leaves: Vec<Vec<u8>> = (0..self.item_count)
+            .filter_map(|index| self.item_bytes(index))
+            .collect();
+        let leaves: Vec<&[u8]> = leaves.iter().map(Vec::as_slice).collect();
+        D::digest_batch(&leaves)
+    }
+
+    /// Get the lowest level at which items `i` and `j` share a digest node
+    ///
+    /// An item shares level 0 with itself. Returns `None` for out-of-range
+    /// indices or items whose ancestors have not been digested together yet.
+    pub fn common_ancestor_level(&self, i: usize, j: usize) -> Option<usize> {
+        if i >= self.item_count || j >= self.item_count {
+            return None;
+        }
+        if i == j {
+            return Some(0);
+        }
+
+        let ancestors_of_i: HashMap<usize, usize> = self.ancestors(NodeId::Item(i)).collect();
+        self.ancestors(NodeId::Item(j))
+            .find(|(record, _)| ancestors_of_i.contains_key(record))
+            .map(|(_, level)| level)
+    }
+
+    /// Walk the digest nodes above `node_id` as (record index, level of the digest)
+    fn ancestors(&self, node_id: NodeId) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let mut current = node_id;
+        std::iter::from_fn(move || {
+            let record = *self.node_records.get(&current)?;
+            current = NodeId::Digest(record);
+            Some((record, self.overflow_records[record].level + 1))
+        })
+    }
+
+    /// Number of path elements a proof for `index` carries
+    ///
+    /// One element per overflow group above the item, plus one for the level
+    /// where the path ends if that level holds other nodes. Lets tests and
+    /// verifiers check proof sizes without building a path. Returns 0 for an
+    /// index that was never appended.
+    pub fn expected_path_length(&self, index: usize) -> usize {
+        if index >= self.item_count {
+            return 0;
+        }
+
+        let mut groups = 0;
+        let mut end_level = 0;
+        for (_, level) in self.ancestors(NodeId::Item(index)) {
+            groups += 1;
+            end_level = level;
+        }
+        let shares_level = self.levels[end_level].len() > 1;
+        groups + usize::from(shares_level)
+    }
+
+    /// Generate a proof for an item at a given index
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self), err)
+    )]
+    pub fn generate_proof(&self, index: usize) -> Result<MembershipProof<T, D>, LazyTowerError> {
+        let cache_key = (index, self.item_count);
+        if let Some(proof) = self
+            .proof_cache
+            .as_ref()
+            .and_then(|cache| cache.get(cache_key))
+        {
+            return Ok(proof);
+        }
+
+        let path = self.proof_path_for(index)?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(path_len = path.elements.len(), "proof path built");
+
+        let proof = self.proof_with_path(index, path)?;
+        if let Some(cache) = &self.proof_cache {
+            cache.insert(cache_key, proof.clone());
+        }
+
+        Ok(proof)
+    }
+
+    /// Generate a proof for the most recently appended item
+    ///
+    /// While the newest item still waits at level 0 its position is tracked,
+    /// so the path is built without searching the current levels for it.
+    /// Once it has overflowed this is the same as `generate_proof`.
+    pub fn generate_proof_latest(&self) -> Result<MembershipProof<T, D>, LazyTowerError> {
+        let index = self
+            .item_count
+            .checked_sub(1)
+            .ok_or(LazyTowerError::InvalidIndex { index: 0, max: 0 })?;
+
+        match self.item_positions.get(&index) {
+            Some(position) if !self.node_records.contains_key(&NodeId::Item(index)) => {
+                let mut path = ProofPath::new();
+                if self.item_count > 1 {
+                    self.current_level_step(position.level, position.index, &mut path);
+                }
+                self.proof_with_path(index, path)
+            }
+            _ => self.generate_proof(index),
+        }
+    }
+
+    /// Prove that the current root follows from the root at `old_count` items
+    ///
+    /// The proof carries the nodes the tower held after `old_count` items and
+    /// every leaf appended since; see `AppendProof` for what it establishes.
+    /// Requires a single width and a tower that still holds every item.
+    pub fn append_proof(&self, old_count: usize) -> Result<AppendProof<D>, LazyTowerError> {
+        if old_count == 0 || old_count > self.item_count {
+            return Err(LazyTowerError::InvalidIndex {
+                index: old_count,
+                max: self.item_count,
+            });
+        }
+        if self.widths.len() != 1 {
+            return Err(LazyTowerError::InconsistentState {
+                reason: "append proofs need a single width".to_string(),
+            });
+        }
+
+        let leaves = (0..self.item_count)
+            .map(|index| {
+                self.leaf_node(index)
+                    .map(|node| match node {
+                        TowerNode::Item(item) => TowerNode::Item(item.as_ref().to_vec()),
+                        TowerNode::Digest(digest) => TowerNode::Digest(digest),
+                    })
+                    .ok_or(LazyTowerError::MissingItemBytes { index })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut proof = AppendProof {
+            width: self.width(),
+            old_count: 0,
+            old_levels: Vec::new(),
+            appended: leaves[..old_count].to_vec(),
+        };
+        proof.old_levels = proof.replay();
+        proof.old_count = old_count;
+        proof.appended = leaves[old_count..].to_vec();
+
+        // Lazy overflows or flushed levels leave a root the replay cannot reach
+        if frontier_root::<D>(&proof.replay()) != self.root_digest() {
+            return Err(LazyTowerError::InconsistentState {
+                reason: "tower levels differ from an eager replay of its items".to_string(),
+            });
+        }
+
+        Ok(proof)
+    }
+
+    /// Pair the proof path for `index` with the item and the current root
+    fn proof_with_path(
+        &self,
+        index: usize,
+        path: ProofPath<D>,
+    ) -> Result<MembershipProof<T, D>, LazyTowerError> {
+        // Get the original item
+        let item = self
+            .get(index)
+            .ok_or(LazyTowerError::ProofGenerationNotImplemented)?
+            .clone();
+
+        // Get current root
+        let root = self
+            .root_digest()
+            .ok_or(LazyTowerError::ProofGenerationNotImplemented)?;
+
+        Ok(MembershipProof {
+            item,
+            path,
+            root,
+            meta: self.meta(index),
+            nonce: self.nonce.clone(),
+        })
+    }
+
+    /// Verify a proof against this tower's current root
+    ///
+    /// Unlike `MembershipProof::verify`, this rejects proofs whose embedded
+    /// root belongs to another tower or an earlier state of this one.
+    pub fn verify(&self, proof: &MembershipProof<T, D>) -> bool {
+        self.root_digest()
+            .is_some_and(|root| proof.root == root && (proof.verify() || self.is_lone_leaf(proof)))
+    }
+
+    /// Verify a proof and check that it proves the item at `expected_index`
+    ///
+    /// A valid proof for one item is otherwise accepted in place of another's.
+    /// Width-2 towers read the index from `MembershipProof::proved_index`;
+    /// other towers compare the path with the one generated for
+    /// `expected_index`.
+    pub fn verify_proof_for_index(
+        &self,
+        proof: &MembershipProof<T, D>,
+        expected_index: usize,
+    ) -> bool {
+        if expected_index >= self.item_count || !self.verify(proof) {
+            return false;
+        }
+        if self.widths == [2] {
+            return proof.proved_index() == Some(expected_index);
+        }
+        self.proof_path_for(expected_index)
+            .is_ok_and(|path| path == proof.path)
+    }
+
+    /// Whether `proof` proves the only leaf of a tower whose root is that leaf
+    ///
+    /// A lone digest leaf is its own root, which a standalone proof cannot
+    /// tell apart from a forged item equal to the root bytes.
+    fn is_lone_leaf(&self, proof: &MembershipProof<T, D>) -> bool {
+        self.item_count == 1
+            && proof.path.elements.is_empty()
+            && proof.meta.is_none()
+            && proof.nonce.is_none()
+            && !self.options.hash_leaves
+            && matches!(self.levels[0].first(), Some(TowerNode::Digest(leaf)) if leaf.as_ref() == proof.item.as_ref())
+    }
+
+    /// Resolve the nodes a proof for `index` passes through
+    ///
+    /// Returns the (level, position) of the proved node at each step, ending
+    /// with its place among the current nodes, for checking that proof
+    /// construction resolves nodes in a stable order.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn proof_traversal(&self, index: usize) -> Result<Vec<(usize, usize)>, LazyTowerError> {
+        if index >= self.item_count {
+            return Err(LazyTowerError::InvalidIndex {
+                index,
+                max: self.item_count,
+            });
+        }
+
+        let mut steps = Vec::new();
+        let mut node_id = NodeId::Item(index);
+        while let Some(&record_index) = self.node_records.get(&node_id) {
+            let record = &self.overflow_records[record_index];
+            let position = record
+                .node_ids
+                .iter()
+                .position(|nid| nid == &node_id)
+                .unwrap_or(0);
+            steps.push((record.level, position));
+            node_id = NodeId::Digest(record_index);
+        }
+
+        if let Some((&key, _)) = self.level_nodes.iter().find(|(_, nid)| **nid == node_id) {
+            steps.push(key);
+        }
+        Ok(steps)
+    }
+
+    /// Export a proof for the item at `index` as a crate-independent bundle
+    pub fn export_proof_bundle(&self, index: usize) -> Result<ProofBundle, LazyTowerError> {
+        let proof = self.generate_proof(index)?;
+
+        Ok(ProofBundle {
+            item: proof.item.as_ref().to_vec(),
+            path: proof.path.encode(),
+            root: proof.root.as_ref().to_vec(),
+            width: self.width(),
+            algorithm: D::algorithm_id().to_string(),
+        })
+    }
+
+    /// Start caching generated proofs until the tower next changes
+    pub fn enable_proof_cache(&mut self) {
+        if self.proof_cache.is_none() {
+            self.proof_cache = Some(ProofCache::new());
+        }
+    }
+
+    /// Reuse the digest of any overflow group whose nodes repeat an earlier one
+    ///
+    /// For datasets with many duplicate items, where separate groups often
+    /// hold the same node bytes. Each distinct group is hashed once; the memo
+    /// keeps a copy of every distinct group's bytes, so it grows with the
+    /// tower. Roots and proofs are unchanged.
+    pub fn enable_group_memo(&mut self) {
+        if self.group_memo.is_none() {
+            self.group_memo = Some(GroupMemo::new());
+        }
+    }
+
+    /// Get the number of distinct groups in the memo, if it is enabled
+    pub fn group_memo_len(&self) -> Option<usize> {
+        self.group_memo.as_ref().map(GroupMemo::len)
+    }
+
+    /// Drop the cached root, top-level digest and proofs after a change
+    fn invalidate_caches(&mut self) {
+        self.cached_root = None;
+        self.top_digest.clear();
+        self.invalidate_proof_cache();
+    }
+
+    /// Drop every cached proof
+    fn invalidate_proof_cache(&self) {
+        if let Some(cache) = &self.proof_cache {
+            cache.clear();
+        }
+    }
+
+    /// Build the proof path for an item without bundling the item or root
+    pub fn proof_path_for(&self, index: usize) -> Result<ProofPath<D>, LazyTowerError> {
+        // Check bounds
+        if self.item_count == 0 || index >= self.item_count {
+            return Err(LazyTowerError::InvalidIndex {
+                index,
+                max: self.item_count,
+            });
+        }
+
+        let mut path = ProofPath::new();
+
+        // Simple case: if there's only one item, no siblings needed
+        if self.item_count == 1 {
+            return Ok(path);
+        }
+
+        // Build proof path from item to root using NodeId tracking
+        let item_node_id = NodeId::Item(index);
+        self.build_proof_path(&item_node_id, &mut path)?;
+
+        Ok(path)
+    }
+}
+
+impl<T: Clone + AsRef<[u8]> + From<Vec<u8>>, D: Digest> LazyTower<T, D> {
+    /// Rebuild a tower of `width` by appending every journal entry in order
+    ///
+    /// Entries appended with metadata are appended with it again. The
+    /// replayed tower keeps journaling. Fails with `InconsistentState` if an
+    /// entry's index is out of sequence or records a leaf digest, which only
+    /// `replay_onto` can append as a digest. Towers built with options, a
+    /// nonce, per-level widths or an overflow policy are rebuilt with
+    /// `replay_onto`.
+    pub fn replay(width: usize, journal: &[JournalEntry]) -> Result<Self, LazyTowerError> {
+        Self::replay_onto(Self::new(width)?, journal)
+    }
+
+    /// Append every journal entry in order to `tower`, which carries the settings
+    ///
+    /// Only items are journaled, so `tower` must be built with the settings of
+    /// the journaled tower to reproduce its root. Entries continue from
+    /// `tower.len()`, so an empty tower replays a whole journal and a
+    /// restored snapshot replays the entries after it. Journaling is enabled
+    /// on the result. Entries recorded by `append_digest` are appended as
+    /// leaf digests again, which needs a `tower` that takes digests: one built
+    /// with `from_leaf_digests` or already holding an appended digest.
+    /// Otherwise they fail with `InconsistentState`.
+    pub fn replay_onto(mut tower: Self, journal: &[JournalEntry]) -> Result<Self, LazyTowerError> {
+        tower.enable_journal();
+        for entry in journal {
+            if entry.index != tower.item_count {
+                return Err(LazyTowerError::InconsistentState {
+                    reason: format!(
+                        "journal entry for item {} found where item {} was expected",
+                        entry.index, tower.item_count
+                    ),
+                });
+            }
+            let item = T::from(entry.bytes.clone());
+            match (entry.meta, tower.item_digest.filter(|_| entry.digest)) {
+                (Some(meta), _) => {
+                    tower.check_item(&item)?;
+                    tower.append_meta_leaf(item, meta)?;
+                }
+                (None, Some(item_digest)) => {
+                    tower.check_item(&item)?;
+                    tower.append_digest_leaf(item, item_digest)?;
+                }
+                (None, None) if entry.digest => {
+                    return Err(LazyTowerError::InconsistentState {
+                        reason: format!(
+                            "journal entry for item {} is a leaf digest but the tower takes none",
+                            entry.index
+                        ),
+                    });
+                }
+                (None, None) => tower.try_append(item)?,
+            }
+        }
+        Ok(tower)
+    }
+
+    /// Append a byte stream as items of `item_size` bytes each
+    ///
+    /// A shorter final chunk is appended as-is. Returns the number of items
+    /// appended; on error the items read before it remain in the tower.
+    pub fn append_from_reader<R: Read>(
+        &mut self,
+        mut reader: R,
+        item_size: usize,
+    ) -> io::Result<usize> {
+        if item_size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "item_size must be greater than 0",
+            ));
+        }
+
+        let mut appended = 0;
+        loop {
+            // Fill a whole chunk unless the stream ends first
+            let mut chunk = vec![0u8; item_size];
+            let mut filled = 0;
+            while filled < item_size {
+                match reader.read(&mut chunk[filled..]) {
+                    Ok(0) => break,
+                    Ok(n) => filled += n,
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(err) => return Err(err),
+                }
+            }
+            if filled == 0 {
+                return Ok(appended);
+            }
+
+            chunk.truncate(filled);
+            self.try_append(T::from(chunk)).map_err(io::Error::other)?;
+            appended += 1;
+
+            if filled < item_size {
+                return Ok(appended);
+            }
+        }
+    }
+}
+
+impl<T: Clone + AsRef<[u8]> + Ord, D: Digest> LazyTower<T, D> {
+    /// Create a tower from items sorted into canonical order
+    ///
+    /// The root depends only on the set of items, not the order they were
+    /// supplied in. Proofs use the index of an item after sorting.
+    pub fn from_sorted_items(width: usize, mut items: Vec<T>) -> Result<Self, LazyTowerError> {
+        items.sort();
+
+        let mut tower = Self::new(width)?;
+        for item in items {
+            tower.try_append(item)?;
+        }
+        Ok(tower)
+    }
+}
+
+impl<B: Clone + Borrow<[u8]>, D: Digest> LazyTower<BorrowedBytes<B>, D> {
+    /// Append an item that exposes its bytes through `Borrow<[u8]>`
+    pub fn append_borrowed(&mut self, item: B) -> Result<(), LazyTowerError> {
+        self.try_append(BorrowedBytes(item))
+    }
+}
+
+impl<V: Clone, E: LeafEncoder<V>, D: Digest> LazyTower<Encoded<V, E>, D> {
+    /// Create a tower committing to the `E` encoding of each value
+    ///
+    /// Values are stored alongside their encoding and the tower hashes only
+    /// the encoding, so proofs carry the value and verify against its
+    /// canonical bytes.
+    pub fn new_with_encoder(width: usize) -> Result<Self, LazyTowerError> {
+        Self::new(width)
+    }
+
+    /// Encode `value` and append it
+    pub fn append_value(&mut self, value: V) -> Result<(), LazyTowerError> {
+        self.try_append(Encoded::new(value))
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: Clone + AsRef<[u8]> + zeroize::Zeroize, D: Digest> LazyTower<T, D> {
+    /// Wipe every stored copy of the items, then empty the tower
+    ///
+    /// Items are held in the item map, at level 0 until they overflow, in
+    /// cached proofs, and as bytes in the journal and group memo. Each copy
+    /// this tower owns alone is zeroized in place before it is freed; storage
+    /// still shared with a clone is left to that clone. The tower keeps its
+    /// configuration, hooks and validator, as `compact` does when it keeps
+    /// nothing.
+    ///
+    /// Copies dropped any other way, including the whole tower going out of
+    /// scope, are only wiped if `T` zeroizes itself on drop, as
+    /// `zeroize::Zeroizing` does.
+    pub fn clear_zeroized(&mut self) {
+        use zeroize::Zeroize;
+
+        if let Some(items) = Arc::get_mut(&mut self.items) {
+            items.values_mut().for_each(T::zeroize);
+        }
+        if let Some(levels) = Arc::get_mut(&mut self.levels) {
+            for node in levels.iter_mut().flatten() {
+                if let TowerNode::Item(item) = node {
+                    item.zeroize();
+                }
+            }
+        }
+        if let Some(cache) = &self.proof_cache {
+            cache
+                .lock()
+                .values_mut()
+                .for_each(|proof| proof.item.zeroize());
+        }
+        if let Some(journal) = self.journal.as_mut().and_then(Arc::get_mut) {
+            journal.iter_mut().for_each(|entry| entry.bytes.zeroize());
+        }
+        if let Some(memo) = self.group_memo.as_mut() {
+            if let Some(digests) = Arc::get_mut(&mut memo.digests) {
+                let digests = digests.get_mut().unwrap_or_else(|p| p.into_inner());
+                for (mut group, _) in digests.drain() {
+                    group.zeroize();
+                }
+            }
+        }
+
+        self.compact(|_, _| false)
+            .expect("a tower rebuilt with no items cannot fail");
+    }
+}
+
+impl<D: Digest> LazyTower<D::Output, D> {
+    /// Create a tower seeded with precomputed leaf digests
+    ///
+    /// Each leaf is appended as a `TowerNode::Digest` at level 0 and overflows
+    /// normally. Proofs are generated with the leaf digest as the proved item.
+    /// A tower holding a single leaf uses that leaf directly as its root.
+    pub fn from_leaf_digests(width: usize, leaves: Vec<D::Output>) -> Result<Self, LazyTowerError> {
+        let mut tower = Self::new(width)?;
+        tower.item_digest = Some(digest_itself::<D>);
+        for leaf in leaves {
+            tower.append_digest(leaf)?;
+        }
+        Ok(tower)
+    }
+
+    /// Append a precomputed leaf digest alongside ordinary items
+    ///
+    /// The digest is stored as a `TowerNode::Digest` and folded into its
+    /// group as is, never passed through `digest_item`. Proofs carry it as the
+    /// proved item; a tower holding only this leaf has it as its root, which
+    /// `LazyTower::verify` accepts with an empty path. In a tower with a nonce
+    /// the digest is bound to the nonce like any other item.
+    pub fn append_digest(&mut self, digest: D::Output) -> Result<(), LazyTowerError> {
+        self.check_item(&digest)?;
+        self.append_digest_leaf(digest, digest_itself::<D>)
+    }
+}
+
+/// Read a leaf digest item as itself
+fn digest_itself<D: Digest>(digest: &D::Output) -> Option<D::Output> {
+    Some(digest.clone())
+}
+
+/// Whether two optional shared buffers are both absent or the same allocation
+fn shared<V>(a: &Option<Arc<V>>, b: &Option<Arc<V>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Check a digest against `D::OUTPUT_LEN`, when the digest declares one
+fn check_digest_len<D: Digest>(digest: &D::Output) -> Result<(), LazyTowerError> {
+    match D::OUTPUT_LEN {
+        Some(expected) if digest.as_ref().len() != expected => {
+            Err(LazyTowerError::DigestLengthMismatch {
+                expected,
+                actual: digest.as_ref().len(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Level-0 node for a plain item under the given nonce and `hash_leaves` option
+fn item_leaf<T: Clone + AsRef<[u8]>, D: Digest>(
+    nonce: Option<&[u8]>,
+    hash_leaves: bool,
+    item: &T,
+) -> TowerNode<T, D> {
+    let node = match nonce {
+        Some(nonce) => TowerNode::Digest(nonce_leaf::<D>(nonce, item.as_ref())),
+        None => TowerNode::Item(item.clone()),
+    };
+    if hash_leaves {
+        TowerNode::Digest(D::digest_item(&node))
+    } else {
+        node
+    }
+}
+
+/// Maximum number of items a uniform tower of `width` can hold below `height` levels
+///
+/// Returns `width.pow(height)`, saturating at `usize::MAX`.
+pub fn max_items_for_height(width: usize, height: usize) -> usize {
+    u32::try_from(height)
+        .ok()
+        .and_then(|height| width.checked_pow(height))
+        .unwrap_or(usize::MAX)
+}
+
+/// Smallest height whose capacity for `width` holds `n` items
+///
+/// Returns the ceiling of `log_width(n)`, so 0 for `n <= 1`. Widths of 0 or 1
+/// never grow and saturate at `usize::MAX` for larger `n`.
+pub fn min_height_for_items(width: usize, n: usize) -> usize {
+    if n <= 1 {
+        return 0;
+    }
+    if width <= 1 {
+        return usize::MAX;
+    }
+
+    let mut height = 0;
+    let mut capacity: usize = 1;
+    while capacity < n {
+        capacity = capacity.saturating_mul(width);
+        height += 1;
+    }
+    height
+}
+
+/// Read access to the state needed to walk a proof path from a leaf to the root
+pub(crate) trait ProofSource<D: Digest> {
+    /// Overflow records in creation order
+    fn overflow_records(&self) -> &[OverflowRecord<D>];
+
+    /// Mapping from NodeId to the index of the overflow record that digested it
+    fn node_records(&self) -> &HashMap<NodeId, usize>;
+
+    /// Mapping from level and index to NodeId for current nodes
+    fn level_nodes(&self) -> &BTreeMap<(usize, usize), NodeId>;
+
+    /// Raw bytes of the item at `index`
+    fn item_bytes(&self, index: usize) -> Option<Vec<u8>>;
+
+    /// Raw bytes of every node currently at `level`
+    fn level_bytes(&self, level: usize) -> Option<Vec<Vec<u8>>>;
+
+    /// Whether level 0 holds `digest_item` of each item instead of raw items
+    fn hashes_leaves(&self) -> bool {
+        false
+    }
+
+    /// Leaf digest of the item at `index` when leaves are hashed
+    fn leaf_digest(&self, index: usize) -> Option<D::Output> {
+        self.item_bytes(index).map(|bytes| D::digest_item(&bytes))
+    }
+
+    /// Build the proof path from a node up to the root
+    fn build_proof_path(
+        &self,
+        node_id: &NodeId,
+        path: &mut ProofPath<D>,
+    ) -> Result<(), LazyTowerError> {
+        let mut current = node_id.clone();
+        while let Some(parent) = self.proof_step(&current, path)? {
+            current = parent;
+        }
+        Ok(())
+    }
+
+    /// Add the path element for one node, returning its parent digest node
+    ///
+    /// Returns `None` once the node sits at a current level, where the path
+    /// ends.
+    fn proof_step(
+        &self,
+        node_id: &NodeId,
+        path: &mut ProofPath<D>,
+    ) -> Result<Option<NodeId>, LazyTowerError> {
+        // Find which overflow record contains this node
+        if let Some(&record_index) = self.node_records().get(node_id) {
+            let record = &self.overflow_records()[record_index];
+
+            // Find position and siblings within this overflow group
+            let mut position = 0;
+
+            if record.level == 0 && self.hashes_leaves() {
+                // Level 0 with hashed leaves: siblings are leaf digests
+                let mut leaf_siblings = Vec::new();
+
+                for (i, nid) in record.node_ids.iter().enumerate() {
+                    if nid == node_id {
+                        position = i;
+                    } else if let NodeId::Item(idx) = nid {
+                        if let Some(digest) = self.leaf_digest(*idx) {
+                            leaf_siblings.push(digest);
+                        }
+                    }
+                }
+
+                path.add_siblings(position, leaf_siblings);
+            } else if record.level == 0 {
+                // Level 0: Use raw siblings (actual item values)
+                let mut raw_siblings = Vec::new();
+
+                for (i, nid) in record.node_ids.iter().enumerate() {
+                    if nid == node_id {
+                        position = i;
+                    } else if let NodeId::Item(idx) = nid {
+                        if let Some(bytes) = self.item_bytes(*idx) {
+                            raw_siblings.push(bytes);
+                        }
+                    }
+                }
+
+                path.add_raw_siblings(position, raw_siblings);
+            } else {
+                // Higher levels: Use digest siblings
+                let mut digest_siblings = Vec::new();
+
+                for (i, nid) in record.node_ids.iter().enumerate() {
+                    if nid == node_id {
+                        position = i;
+                    } else if let NodeId::Digest(child_record) = nid {
+                        // Look up the digest produced by the sibling's record
+                        digest_siblings
+                            .push(self.overflow_records()[*child_record].result_digest.clone());
+                    }
+                }
+
+                path.add_siblings(position, digest_siblings);
+            }
+
+            // Continue building path for the parent digest
+            return Ok(Some(NodeId::Digest(record_index)));
+        }
+
+        // If not in any overflow record, check if it's currently at a level
+        for ((level, index), nid) in self.level_nodes() {
+            if nid == node_id {
+                self.current_level_step(*level, *index, path);
+                return Ok(None);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Add the path element for the node at `index` among the current nodes of
+    /// `level`, if it has siblings there
+    fn current_level_step(&self, level: usize, index: usize, path: &mut ProofPath<D>) {
+        if level == 0 && self.hashes_leaves() {
+            // Hashed leaves still waiting at level 0 pair with leaf digests
+            let count = self.level_bytes(0).map_or(0, |nodes| nodes.len());
+            if count > 1 {
+                let siblings = (0..count)
+                    .filter(|&i| i != index)
+                    .filter_map(|i| match self.level_nodes().get(&(0, i)) {
+                        Some(NodeId::Item(idx)) => self.leaf_digest(*idx),
+                        _ => None,
+                    })
+                    .collect();
+                path.add_siblings(index, siblings);
+            }
+            return;
+        }
+        if let Some(level_bytes) = self.level_bytes(level) {
+            if level_bytes.len() > 1 {
+                // Has siblings at this level
+                let siblings = level_bytes
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != index)
+                    .map(|(_, bytes)| bytes)
+                    .collect();
+                path.add_raw_siblings(index, siblings);
+            }
+        }
+    }
+}
+
+/// Mutable access to one tower item, refreshing the tower when dropped
+///
+/// Returned by `LazyTower::get_mut`.
+#[derive(Debug)]
+pub struct ItemGuard<'a, T: Clone + AsRef<[u8]>, D: Digest> {
+    /// Tower the item belongs to
+    tower: &'a mut LazyTower<T, D>,
+    /// Index of the item
+    index: usize,
+    /// Copy of the item, stored back on drop
+    item: Option<T>,
+}
+
+impl<T: Clone + AsRef<[u8]>, D: Digest> Deref for ItemGuard<'_, T, D> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.item.as_ref().expect("item present until drop")
+    }
+}
+
+impl<T: Clone + AsRef<[u8]>, D: Digest> DerefMut for ItemGuard<'_, T, D> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.item.as_mut().expect("item present until drop")
+    }
+}
+
+impl<T: Clone + AsRef<[u8]>, D: Digest> ItemGuard<'_, T, D> {
+    /// Store the edited item, returning why it was rejected if it was
+    ///
+    /// Dropping the guard does the same and discards the error. On error the
+    /// tower keeps the item as it was before the edit.
+    pub fn commit(mut self) -> Result<(), LazyTowerError> {
+        self.write_back()
+    }
+
+    /// Check the edited item and store it, restoring the old item on error
+    fn write_back(&mut self) -> Result<(), LazyTowerError> {
+        let Some(item) = self.item.take() else {
+            return Ok(());
+        };
+        self.tower.check_item(&item)?;
+
+        let previous = Arc::make_mut(&mut self.tower.items).insert(self.index, item);
+        if let Err(err) = self.tower.refresh_item(self.index) {
+            // Digests of the wrong length were not stored; keep the old item
+            if let Some(previous) = previous {
+                Arc::make_mut(&mut self.tower.items).insert(self.index, previous);
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+impl<T: Clone + AsRef<[u8]>, D: Digest> Drop for ItemGuard<'_, T, D> {
+    fn drop(&mut self) {
+        let _ = self.write_back();
+    }
+}
+
+/// Owning iterator over a tower's items in index order
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    /// Items not yet yielded, keyed by index
+    items: HashMap<usize, T>,
+    /// Index of the next item to yield
+    next: usize,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while !self.items.is_empty() {
+            let index = self.next;
+            self.next += 1;
+            if let Some(item) = self.items.remove(&index) {
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.items.len(), Some(self.items.len()))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T: Clone, D: Digest> IntoIterator for LazyTower<T, D> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Consume the tower, yielding the appended items in index order
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            items: Arc::try_unwrap(self.items).unwrap_or_else(|items| (*items).clone()),
+            next: 0,
+        }
+    }
+}
+
+impl<T: Clone + AsRef<[u8]>, D: Digest> ProofSource<D> for LazyTower<T, D> {
+    fn overflow_records(&self) -> &[OverflowRecord<D>] {
+        &self.overflow_records
+    }
+
+    fn node_records(&self) -> &HashMap<NodeId, usize> {
+        &self.node_records
+    }
+
+    fn level_nodes(&self) -> &BTreeMap<(usize, usize), NodeId> {
+        &self.level_nodes
+    }
+
+    fn item_bytes(&self, index: usize) -> Option<Vec<u8>> {
+        let item = self.items.get(&index)?;
+        let bytes = match self.metadata.get(&index) {
+            Some(&meta) => meta_leaf::<D>(item.as_ref(), meta).as_ref().to_vec(),
+            None => item.as_ref().to_vec(),
+        };
+        Some(match &self.nonce {
+            Some(nonce) => nonce_leaf::<D>(nonce, &bytes).as_ref().to_vec(),
+            None => bytes,
+        })
+    }
+
+    fn level_bytes(&self, level: usize) -> Option<Vec<Vec<u8>>> {
+        self.levels
+            .get(level)
+            .map(|nodes| nodes.iter().map(|node| node.as_ref().to_vec()).collect())
+    }
+
+    fn hashes_leaves(&self) -> bool {
+        self.options.hash_leaves
+    }
 }
 
 #[cfg(test)]
@@ -372,4 +2926,27 @@ mod tests {
             _ => panic!("Expected InvalidWidth error"),
         }
     }
+
+    #[test]
+    fn test_validate_after_appends() {
+        let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(3).unwrap();
+        assert!(tower.validate().is_ok());
+
+        for i in 0..20u8 {
+            tower.append(vec![i]);
+            assert!(tower.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_validate_detects_untracked_node() {
+        let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(3).unwrap();
+        tower.append(vec![1]);
+        Arc::make_mut(&mut tower.level_nodes).clear();
+
+        assert!(matches!(
+            tower.validate(),
+            Err(LazyTowerError::InconsistentState { .. })
+        ));
+    }
 }
@@ -0,0 +1,60 @@
+//! Tests for the Accumulator trait across tower variants
+
+use lazytower_rs::{Accumulator, Digest, LazyTower, LazyTowerConst};
+
+/// Mock digest for testing
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockDigest;
+
+impl Digest for MockDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut result = b"digest(".to_vec();
+        result.extend_from_slice(item.as_ref());
+        result.extend_from_slice(b")");
+        result
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut result = b"digest_items[".to_vec();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                result.extend_from_slice(b",");
+            }
+            result.extend_from_slice(item.as_ref());
+        }
+        result.extend_from_slice(b"]");
+        result
+    }
+}
+
+/// Append `count` items and check every proof verifies, returning the root
+fn build_and_prove<A: Accumulator<Vec<u8>, MockDigest>>(acc: &mut A, count: u8) -> Vec<u8> {
+    assert!(acc.is_empty());
+    for i in 0..count {
+        acc.append(vec![b'a' + i]);
+    }
+    assert_eq!(acc.len(), count as usize);
+
+    let root = acc.root_digest().unwrap();
+    for i in 0..acc.len() {
+        let proof = acc.generate_proof(i).unwrap();
+        assert_eq!(proof.root, root);
+        assert!(proof.verify(), "Proof verification failed for item {}", i);
+    }
+    root
+}
+
+#[test]
+fn test_accumulator_generic_over_tower_variants() {
+    let mut dynamic: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    let mut fixed: LazyTowerConst<Vec<u8>, MockDigest, 2> = LazyTowerConst::new();
+
+    let dynamic_root = build_and_prove(&mut dynamic, 8);
+    let fixed_root = build_and_prove(&mut fixed, 8);
+
+    assert_eq!(dynamic_root, fixed_root);
+    assert_eq!(fixed.width(), 2);
+    assert_eq!(fixed.as_tower().width(), 2);
+}
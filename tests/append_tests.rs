@@ -1,6 +1,6 @@
 //! Tests for LazyTower append operation
 
-use lazytower_rs::{Digest, LazyTower, TowerNode};
+use lazytower_rs::{Digest, LazyTower, LazyTowerError, TowerMetrics, TowerNode, TowerStats};
 
 /// Test item that can be converted to bytes
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -176,3 +176,580 @@ fn test_large_scale_append() {
     assert_eq!(tower.len(), 1000);
     assert!(tower.height() > 1); // Should have multiple levels
 }
+
+/// Digest that panics when asked to digest a group containing `boom`
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PanickingDigest;
+
+impl Digest for PanickingDigest {
+    type Output = TestDigestOutput;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        TestDigest::digest_item(item)
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        if items.iter().any(|item| item.as_ref() == b"boom") {
+            panic!("digest failure");
+        }
+        TestDigest::digest_items(items)
+    }
+}
+
+#[test]
+fn test_append_panicking_digest_leaves_tower_consistent() {
+    let mut tower: LazyTower<TestItem, PanickingDigest> = LazyTower::new(2).unwrap();
+
+    // Three items leave level 0 with one node and level 1 with one digest
+    for i in 0..3 {
+        tower.append(TestItem(format!("item{}", i).into_bytes()));
+    }
+    let root_before = tower.root_digest();
+
+    // The fourth item cascades through two overflows and panics on the first
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        tower.append(TestItem(b"boom".to_vec()));
+    }));
+    assert!(result.is_err());
+
+    assert!(tower.validate().is_ok());
+    assert_eq!(tower.len(), 3);
+    assert_eq!(tower.root_digest(), root_before);
+
+    // The tower remains usable after the failed append
+    tower.append(TestItem(b"item3".to_vec()));
+    assert!(tower.validate().is_ok());
+    assert_eq!(tower.len(), 4);
+}
+
+#[test]
+fn test_widths_for_uniform_tower() {
+    let tower: LazyTower<TestItem, TestDigest> = LazyTower::new(4).unwrap();
+    assert_eq!(tower.widths(), &[4]);
+    assert_eq!(tower.width(), 4);
+}
+
+#[test]
+fn test_append_with_per_level_widths() {
+    let mut tower: LazyTower<TestItem, TestDigest> =
+        LazyTower::new_with_widths(vec![2, 3]).unwrap();
+    assert_eq!(tower.widths(), &[2, 3]);
+    assert_eq!(tower.width(), 2);
+
+    // Level 0 overflows every 2 items, level 1 and above every 3 digests
+    for i in 0..6 {
+        tower.append(TestItem(vec![b'a' + i]));
+    }
+
+    assert_eq!(tower.level(0).unwrap().len(), 0);
+    assert_eq!(tower.level(1).unwrap().len(), 0);
+    assert_eq!(
+        tower.level(2).unwrap()[0],
+        TowerNode::Digest(TestDigestOutput("D[D[a,b],D[c,d],D[e,f]]".to_string()))
+    );
+    assert!(tower.validate().is_ok());
+
+    // Higher levels reuse the last configured width
+    for i in 0..12 {
+        tower.append(TestItem(format!("x{}", i).into_bytes()));
+    }
+    assert_eq!(tower.level(2).unwrap().len(), 0);
+    assert_eq!(tower.level(3).unwrap().len(), 1);
+    assert!(tower.validate().is_ok());
+}
+
+#[test]
+fn test_new_with_widths_rejects_invalid_widths() {
+    let empty: Result<LazyTower<TestItem, TestDigest>, _> = LazyTower::new_with_widths(vec![]);
+    assert_eq!(
+        empty.unwrap_err(),
+        LazyTowerError::InvalidWidth { width: 0 }
+    );
+
+    let narrow: Result<LazyTower<TestItem, TestDigest>, _> = LazyTower::new_with_widths(vec![4, 1]);
+    assert_eq!(
+        narrow.unwrap_err(),
+        LazyTowerError::InvalidWidth { width: 1 }
+    );
+}
+
+#[test]
+fn test_logical_height() {
+    let mut tower: LazyTower<TestItem, TestDigest> = LazyTower::new(3).unwrap();
+    assert_eq!(tower.logical_height(), 0);
+
+    tower.append(TestItem(b"a".to_vec()));
+    assert_eq!(tower.logical_height(), 1);
+
+    // Filling a level moves its digest up, leaving the source level empty
+    tower.append(TestItem(b"b".to_vec()));
+    tower.append(TestItem(b"c".to_vec()));
+    assert_eq!(tower.logical_height(), 2);
+    assert!(tower.height() >= tower.logical_height());
+
+    for i in 0..6 {
+        tower.append(TestItem(vec![b'd' + i]));
+    }
+    assert_eq!(tower.logical_height(), 3);
+    assert_eq!(tower.level(0).unwrap().len(), 0);
+    assert_eq!(tower.level(1).unwrap().len(), 0);
+}
+
+#[test]
+fn test_metrics_disabled_by_default() {
+    let mut tower: LazyTower<TestItem, TestDigest> = LazyTower::new(2).unwrap();
+    tower.append(TestItem(b"a".to_vec()));
+    assert!(tower.metrics().is_none());
+}
+
+#[test]
+fn test_metrics_after_known_workload() {
+    let mut tower: LazyTower<TestItem, TestDigest> = LazyTower::new(2).unwrap();
+    tower.enable_metrics();
+
+    for i in 0..8 {
+        tower.append(TestItem(format!("item{}", i).into_bytes()));
+    }
+
+    // 4 overflows at level 0, 2 at level 1 and 1 at level 2
+    assert_eq!(
+        tower.metrics(),
+        Some(&TowerMetrics {
+            total_appends: 8,
+            total_overflows: 7,
+            total_digest_items_calls: 7,
+        })
+    );
+}
+
+#[test]
+fn test_stats_after_known_workload() {
+    let mut tower: LazyTower<TestItem, TestDigest> = LazyTower::new(3).unwrap();
+    tower.enable_metrics();
+    for i in 0..10 {
+        tower.append(TestItem(format!("item{}", i).into_bytes()));
+    }
+
+    // Item 9 waits at level 0 beside the level 2 digest of items 0..9
+    let stats = tower.stats();
+    assert_eq!(
+        stats,
+        TowerStats {
+            item_count: 10,
+            live_count: 2,
+            height: 3,
+            logical_height: 3,
+            overflow_count: 4,
+            width: 3,
+            root: tower.root_digest(),
+        }
+    );
+    assert_eq!(stats.item_count, tower.len());
+    assert_eq!(stats.height, tower.height());
+    assert_eq!(stats.logical_height, tower.logical_height());
+    assert_eq!(stats.width, tower.width());
+    assert_eq!(
+        stats.overflow_count as u64,
+        tower.metrics().unwrap().total_overflows
+    );
+    let live: usize = (0..tower.height())
+        .map(|level| tower.level(level).unwrap().len())
+        .sum();
+    assert_eq!(stats.live_count, live);
+}
+
+#[test]
+fn test_per_level_pending_counts() {
+    let mut tower: LazyTower<TestItem, TestDigest> = LazyTower::new(3).unwrap();
+    for i in 0..23 {
+        tower.append(TestItem(vec![i]));
+    }
+
+    // 23 = 2 * 9 + 1 * 3 + 2: two level-2 digests, one level-1 digest and
+    // two items at level 0
+    let nodes: Vec<usize> = (0..4).map(|level| tower.nodes_at_level(level)).collect();
+    assert_eq!(nodes, vec![2, 1, 2, 0]);
+    let pending: Vec<usize> = (0..4).map(|level| tower.pending_at_level(level)).collect();
+    assert_eq!(pending, vec![1, 2, 1, 3]);
+
+    for level in 0..tower.height() {
+        assert_eq!(
+            tower.nodes_at_level(level),
+            tower.level(level).unwrap().len()
+        );
+    }
+
+    // One more item fills level 0 and cascades into level 1
+    tower.append(TestItem(vec![23]));
+    assert_eq!(tower.nodes_at_level(0), 0);
+    assert_eq!(tower.pending_at_level(1), 1);
+}
+
+#[test]
+fn test_try_append_all_stops_at_source_error() {
+    let mut tower: LazyTower<TestItem, TestDigest> = LazyTower::new(2).unwrap();
+    tower.append(TestItem(b"existing".to_vec()));
+
+    let source = vec![
+        Ok(TestItem(b"a".to_vec())),
+        Ok(TestItem(b"b".to_vec())),
+        Err("line 3: bad record"),
+        Ok(TestItem(b"c".to_vec())),
+    ];
+    let result = tower.try_append_all(source.into_iter());
+
+    assert_eq!(result, Err("line 3: bad record"));
+    assert_eq!(tower.len(), 3);
+    assert_eq!(tower.get(2), Some(&TestItem(b"b".to_vec())));
+
+    let clean = vec![Ok::<_, String>(TestItem(b"c".to_vec()))];
+    assert_eq!(tower.try_append_all(clean.into_iter()), Ok(vec![3]));
+}
+
+#[test]
+fn test_append_stream_flushes_every_n_items() {
+    let items: Vec<TestItem> = (0..10).map(|i| TestItem(vec![b'a' + i])).collect();
+
+    let mut tower: LazyTower<TestItem, TestDigest> = LazyTower::new(2).unwrap();
+    let mut flushes = Vec::new();
+    tower.append_stream(items.clone(), 3, |root| flushes.push(root.clone()));
+
+    assert_eq!(tower.len(), 10);
+    assert_eq!(flushes.len(), 3);
+
+    // Each flushed root matches a tower holding the first 3, 6 and 9 items
+    for (flush, count) in flushes.iter().zip([3, 6, 9]) {
+        let mut expected: LazyTower<TestItem, TestDigest> = LazyTower::new(2).unwrap();
+        for item in &items[..count] {
+            expected.append(item.clone());
+        }
+        assert_eq!(Some(flush.clone()), expected.root_digest());
+    }
+}
+
+#[test]
+fn test_tower_node_conversion_helpers() {
+    let item: TowerNode<TestItem, TestDigest> = TestItem(b"A".to_vec()).into();
+    assert_eq!(item, TowerNode::Item(TestItem(b"A".to_vec())));
+    assert_eq!(item.as_ref(), b"A");
+
+    let digest: TowerNode<TestItem, TestDigest> =
+        TowerNode::digest(TestDigestOutput("D[A,B]".to_string()));
+    assert_eq!(
+        digest,
+        TowerNode::Digest(TestDigestOutput("D[A,B]".to_string()))
+    );
+    assert_eq!(digest.as_ref(), b"D[A,B]");
+
+    // Items that are digests still convert to item nodes
+    let node: TowerNode<TestDigestOutput, TestDigest> =
+        TowerNode::from(TestDigestOutput("x".to_string()));
+    assert!(matches!(node, TowerNode::Item(_)));
+}
+
+#[test]
+fn test_common_ancestor_level() {
+    let mut tower: LazyTower<TestItem, TestDigest> = LazyTower::new(2).unwrap();
+    for i in 0..4u8 {
+        tower.append(TestItem(vec![b'A' + i]));
+    }
+
+    assert_eq!(tower.common_ancestor_level(0, 1), Some(1));
+    assert_eq!(tower.common_ancestor_level(2, 3), Some(1));
+    assert_eq!(tower.common_ancestor_level(0, 3), Some(2));
+    assert_eq!(tower.common_ancestor_level(3, 0), Some(2));
+    assert_eq!(tower.common_ancestor_level(1, 1), Some(0));
+    assert_eq!(tower.common_ancestor_level(0, 4), None);
+
+    // A fifth item has not been digested with anything yet
+    tower.append(TestItem(b"E".to_vec()));
+    assert_eq!(tower.common_ancestor_level(0, 4), None);
+}
+
+#[test]
+fn test_append_from_reader_chunks_stream() {
+    let mut tower: LazyTower<Vec<u8>, TestDigest> = LazyTower::new(2).unwrap();
+    let data: Vec<u8> = (0..10).collect();
+
+    let appended = tower
+        .append_from_reader(std::io::Cursor::new(data), 3)
+        .unwrap();
+
+    assert_eq!(appended, 4);
+    assert_eq!(tower.len(), 4);
+    assert_eq!(tower.get(0), Some(&vec![0, 1, 2]));
+    assert_eq!(tower.get(2), Some(&vec![6, 7, 8]));
+    assert_eq!(tower.get(3), Some(&vec![9]));
+
+    for index in 0..4 {
+        assert!(tower.generate_proof(index).unwrap().verify());
+    }
+}
+
+#[test]
+fn test_append_from_reader_rejects_zero_item_size() {
+    let mut tower: LazyTower<Vec<u8>, TestDigest> = LazyTower::new(2).unwrap();
+    let result = tower.append_from_reader(std::io::Cursor::new(vec![1, 2, 3]), 0);
+
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    assert!(tower.is_empty());
+}
+
+#[test]
+fn test_into_iter_yields_owned_items_in_order() {
+    let mut tower: LazyTower<Vec<u8>, TestDigest> = LazyTower::new(3).unwrap();
+    let expected: Vec<Vec<u8>> = (0..20u8).map(|i| vec![i, i.wrapping_mul(7)]).collect();
+    for item in &expected {
+        tower.append(item.clone());
+    }
+
+    let iter = tower.into_iter();
+    assert_eq!(iter.len(), 20);
+    let items: Vec<Vec<u8>> = iter.collect();
+    assert_eq!(items, expected);
+
+    let empty: LazyTower<Vec<u8>, TestDigest> = LazyTower::new(2).unwrap();
+    assert_eq!(empty.into_iter().count(), 0);
+}
+
+#[test]
+fn test_overflow_hook_sees_final_levels() {
+    use std::sync::{Arc, Mutex};
+
+    type Snapshot = (usize, Vec<usize>, TestDigestOutput);
+    let seen: Arc<Mutex<Vec<Snapshot>>> = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::clone(&seen);
+
+    let mut tower: LazyTower<Vec<u8>, TestDigest> = LazyTower::new(2).unwrap();
+    tower.on_overflow(move |tower, level, _digest| {
+        let lens = (0..tower.height())
+            .map(|index| tower.level(index).unwrap().len())
+            .collect();
+        recorder
+            .lock()
+            .unwrap()
+            .push((level, lens, tower.root_digest().unwrap()));
+    });
+
+    for i in 0..7u8 {
+        tower.append(vec![i]);
+    }
+    seen.lock().unwrap().clear();
+
+    // The eighth item cascades through three levels and creates a fourth
+    tower.append(vec![7]);
+    let final_lens: Vec<usize> = (0..tower.height())
+        .map(|index| tower.level(index).unwrap().len())
+        .collect();
+    assert_eq!(final_lens, vec![0, 0, 0, 1]);
+
+    let seen = seen.lock().unwrap();
+    let levels: Vec<usize> = seen.iter().map(|(level, _, _)| *level).collect();
+    assert_eq!(levels, vec![0, 1, 2]);
+    for (_, lens, root) in seen.iter() {
+        assert_eq!(lens, &final_lens);
+        assert_eq!(Some(root), tower.root_digest().as_ref());
+    }
+}
+
+#[test]
+fn test_clone_shares_storage_until_mutated() {
+    type Tower = LazyTower<Vec<u8>, TestDigest>;
+    let configurations: [fn(&mut Tower); 3] =
+        [|_| {}, Tower::enable_journal, Tower::enable_history];
+
+    for configure in configurations {
+        let mut tower: LazyTower<Vec<u8>, TestDigest> = LazyTower::new(4).unwrap();
+        configure(&mut tower);
+        for i in 0..10_000u32 {
+            tower.append(i.to_le_bytes().to_vec());
+        }
+        let root = tower.root_digest();
+        let proof = tower.generate_proof(1234).unwrap();
+
+        let bottom = tower.level(0).unwrap().len();
+        let journaled = tower.journal().len();
+        let recorded = tower.history().count();
+
+        let mut snapshot = tower.clone();
+        assert!(snapshot.shares_storage_with(&tower));
+        assert_eq!(snapshot.root_digest(), root);
+
+        snapshot.append(b"extra".to_vec());
+        assert!(!snapshot.shares_storage_with(&tower));
+        assert_eq!(snapshot.len(), 10_001);
+        assert_eq!(snapshot.level(0).unwrap().len(), bottom + 1);
+
+        assert_eq!(tower.len(), 10_000);
+        assert_eq!(tower.level(0).unwrap().len(), bottom);
+        assert_eq!(tower.journal().len(), journaled);
+        assert_eq!(tower.history().count(), recorded);
+        assert_eq!(
+            snapshot.history().count(),
+            recorded + usize::from(recorded > 0)
+        );
+        assert_eq!(tower.root_digest(), root);
+        assert_eq!(tower.generate_proof(1234).unwrap().path, proof.path);
+        assert!(tower.verify(&proof));
+    }
+}
+
+#[test]
+fn test_peek_next_overflow_digest_predicts_append() {
+    use std::sync::{Arc, Mutex};
+
+    let seen: Arc<Mutex<Vec<TestDigestOutput>>> = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::clone(&seen);
+
+    let mut tower: LazyTower<TestItem, TestDigest> = LazyTower::new(3).unwrap();
+    tower.on_overflow(move |_, level, digest| {
+        if level == 0 {
+            recorder.lock().unwrap().push(digest.clone());
+        }
+    });
+
+    for i in 0..20u8 {
+        let item = TestItem(vec![b'a' + i]);
+        let predicted = tower.peek_next_overflow_digest(&item);
+        assert_eq!(tower.would_overflow(), predicted.is_some());
+        assert_eq!(predicted.is_some(), i % 3 == 2);
+
+        seen.lock().unwrap().clear();
+        tower.append(item);
+        let actual = seen.lock().unwrap().first().cloned();
+        assert_eq!(predicted, actual, "append {}", i);
+    }
+    assert_eq!(tower.len(), 20);
+}
+
+#[test]
+fn test_journal_replay_reproduces_root() {
+    let mut tower: LazyTower<Vec<u8>, TestDigest> = LazyTower::new(3).unwrap();
+    tower.append(b"before".to_vec());
+    assert!(tower.journal().is_empty());
+
+    tower.enable_journal();
+    for i in 0..20u8 {
+        if i % 5 == 0 {
+            tower.append_with_meta(vec![b'a' + i], u64::from(i));
+        } else {
+            tower.append(vec![b'a' + i]);
+        }
+    }
+    assert_eq!(tower.journal().len(), 20);
+    assert_eq!(tower.journal()[0].index, 1);
+    assert_eq!(tower.journal()[5].meta, Some(5));
+    assert_eq!(tower.journal()[6].meta, None);
+
+    // Only appends after enabling are journaled
+    assert!(matches!(
+        LazyTower::<Vec<u8>, TestDigest>::replay(3, tower.journal()),
+        Err(LazyTowerError::InconsistentState { .. })
+    ));
+
+    // Replaying onto a tower holding the earlier appends resumes from there
+    let mut snapshot: LazyTower<Vec<u8>, TestDigest> = LazyTower::new(3).unwrap();
+    snapshot.append(b"before".to_vec());
+    let resumed = LazyTower::replay_onto(snapshot, tower.journal()).unwrap();
+    assert_eq!(resumed.root_digest(), tower.root_digest());
+
+    let mut journaled: LazyTower<Vec<u8>, TestDigest> = LazyTower::new(3).unwrap();
+    journaled.enable_journal();
+    for i in 0..20u8 {
+        if i % 5 == 0 {
+            journaled.append_with_meta(vec![i], u64::from(i));
+        } else {
+            journaled.append(vec![i]);
+        }
+    }
+
+    let replayed = LazyTower::<Vec<u8>, TestDigest>::replay(3, journaled.journal()).unwrap();
+    assert_eq!(replayed.len(), journaled.len());
+    assert_eq!(replayed.root_digest(), journaled.root_digest());
+    assert_eq!(replayed.meta(10), Some(10));
+    assert_eq!(replayed.journal(), journaled.journal());
+    assert_eq!(
+        replayed.generate_proof(7).unwrap().path,
+        journaled.generate_proof(7).unwrap().path
+    );
+}
+
+#[test]
+fn test_journal_replay_onto_tower_with_settings() {
+    let configured = || {
+        vec![
+            LazyTower::<Vec<u8>, TestDigest>::new_with_nonce(3, b"nonce".to_vec()).unwrap(),
+            LazyTower::<Vec<u8>, TestDigest>::new_with_widths(vec![2, 3]).unwrap(),
+        ]
+    };
+
+    for (mut tower, empty) in configured().into_iter().zip(configured()) {
+        tower.enable_journal();
+        for i in 0..14u8 {
+            if i % 4 == 0 {
+                tower.append_with_meta(vec![i], u64::from(i));
+            } else {
+                tower.append(vec![i]);
+            }
+        }
+
+        // `replay` only knows the bottom width
+        let plain = LazyTower::<Vec<u8>, TestDigest>::replay(3, tower.journal()).unwrap();
+        assert_ne!(plain.root_digest(), tower.root_digest());
+
+        let replayed = LazyTower::replay_onto(empty, tower.journal()).unwrap();
+        assert_eq!(replayed.root_digest(), tower.root_digest());
+        assert_eq!(replayed.journal(), tower.journal());
+    }
+}
+
+#[test]
+fn test_append_with_cascade_reports_overflowed_levels() {
+    let mut tower = LazyTower::<TestItem, TestDigest>::new(2).unwrap();
+    let depths: Vec<usize> = (0..16u8)
+        .map(|i| {
+            let (index, depth) = tower.append_with_cascade(TestItem(vec![i]));
+            assert_eq!(index, i as usize);
+            depth
+        })
+        .collect();
+
+    assert_eq!(depths, vec![0, 1, 0, 2, 0, 1, 0, 3, 0, 1, 0, 2, 0, 1, 0, 4]);
+    assert_eq!(tower.height(), 5);
+}
+
+#[test]
+fn test_append_batch_with_progress_reaches_total() {
+    let mut tower = LazyTower::<TestItem, TestDigest>::new(4).unwrap();
+    let items: Vec<TestItem> = (0..1000u32)
+        .map(|i| TestItem(i.to_le_bytes().to_vec()))
+        .collect();
+
+    let mut reports = Vec::new();
+    let indices = tower
+        .append_batch_with_progress(items, |appended, total| reports.push((appended, total)))
+        .unwrap();
+
+    assert_eq!(indices, (0..1000).collect::<Vec<_>>());
+    assert_eq!(tower.len(), 1000);
+    assert_eq!(reports.len(), 100);
+    assert_eq!(reports[0], (10, 1000));
+    assert_eq!(reports.last(), Some(&(1000, 1000)));
+    assert!(reports.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+    // Short batches report every item; empty ones never report
+    let mut reports = Vec::new();
+    tower
+        .append_batch_with_progress(vec![TestItem(vec![1]), TestItem(vec![2])], |a, t| {
+            reports.push((a, t))
+        })
+        .unwrap();
+    assert_eq!(reports, vec![(1, 2), (2, 2)]);
+
+    let mut called = false;
+    tower
+        .append_batch_with_progress(Vec::new(), |_, _| called = true)
+        .unwrap();
+    assert!(!called);
+    assert_eq!(tower.len(), 1002);
+}
@@ -0,0 +1,39 @@
+//! Tests for tower capacity limits and sizing helpers
+
+use lazytower_rs::{max_items_for_height, min_height_for_items};
+
+#[test]
+fn test_max_items_for_height_known_values() {
+    assert_eq!(max_items_for_height(4, 3), 64);
+    assert_eq!(max_items_for_height(2, 10), 1024);
+    assert_eq!(max_items_for_height(16, 0), 1);
+}
+
+#[test]
+fn test_max_items_for_height_saturates() {
+    assert_eq!(max_items_for_height(4, 100), usize::MAX);
+    assert_eq!(max_items_for_height(usize::MAX, 2), usize::MAX);
+    assert_eq!(max_items_for_height(2, usize::MAX), usize::MAX);
+}
+
+#[test]
+fn test_min_height_for_items_known_values() {
+    assert_eq!(min_height_for_items(4, 0), 0);
+    assert_eq!(min_height_for_items(4, 1), 0);
+    assert_eq!(min_height_for_items(4, 4), 1);
+    assert_eq!(min_height_for_items(4, 5), 2);
+    assert_eq!(min_height_for_items(4, 64), 3);
+    assert_eq!(min_height_for_items(4, 65), 4);
+
+    for n in [2, 17, 1000, 123_456] {
+        let height = min_height_for_items(3, n);
+        assert!(max_items_for_height(3, height) >= n);
+        assert!(max_items_for_height(3, height - 1) < n);
+    }
+}
+
+#[test]
+fn test_min_height_for_items_saturates() {
+    assert_eq!(min_height_for_items(2, usize::MAX), usize::BITS as usize);
+    assert_eq!(min_height_for_items(1, 2), usize::MAX);
+}
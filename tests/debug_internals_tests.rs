@@ -0,0 +1,85 @@
+//! Tests for the debug-only mutable accessors
+
+#![cfg(feature = "debug-internals")]
+
+use lazytower_rs::{Digest, LazyTower, LazyTowerError, TowerNode};
+
+/// Mock digest for testing
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockDigest;
+
+impl Digest for MockDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut result = b"digest(".to_vec();
+        result.extend_from_slice(item.as_ref());
+        result.extend_from_slice(b")");
+        result
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut result = b"digest_items[".to_vec();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                result.extend_from_slice(b",");
+            }
+            result.extend_from_slice(item.as_ref());
+        }
+        result.extend_from_slice(b"]");
+        result
+    }
+}
+
+#[test]
+fn test_level_mut_corruption_is_detected() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(4).unwrap();
+    for i in 0..6u8 {
+        tower.append(vec![i]);
+    }
+    assert!(tower.validate().is_ok());
+
+    // Push a node that no tracking structure knows about
+    tower.level_mut(0).unwrap().push(TowerNode::Item(vec![99]));
+
+    assert!(matches!(
+        tower.validate(),
+        Err(LazyTowerError::InconsistentState { .. })
+    ));
+    assert!(tower.level_mut(9).is_none());
+}
+
+#[test]
+fn test_invalidate_root_cache_exposes_edits() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(4).unwrap();
+    for i in 0..3u8 {
+        tower.append(vec![i]);
+    }
+    let original = tower.recompute_root();
+
+    // The cached root hides the edit until it is invalidated
+    tower.level_mut(0).unwrap()[0] = TowerNode::Item(vec![42]);
+    assert_eq!(tower.root_digest(), original);
+
+    tower.invalidate_root_cache();
+    assert_ne!(tower.root_digest(), original);
+}
+
+#[test]
+fn test_try_append_at_max_count_is_capacity_exceeded() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(4).unwrap();
+    tower.append(vec![1]);
+
+    tower.set_item_count(usize::MAX);
+    assert_eq!(
+        tower.try_append(vec![2]),
+        Err(LazyTowerError::CapacityExceeded)
+    );
+    assert_eq!(tower.len(), usize::MAX);
+    assert_eq!(tower.level(0).unwrap().len(), 1);
+
+    // One below the limit still accepts an item
+    tower.set_item_count(usize::MAX - 1);
+    assert!(tower.try_append(vec![3]).is_ok());
+    assert_eq!(tower.len(), usize::MAX);
+}
@@ -0,0 +1,42 @@
+//! Tests for deriving Digest from a RustCrypto hasher
+
+#![cfg(feature = "derive")]
+
+use blake2::{Blake2b512, Digest as _};
+use lazytower_rs::{Digest, LazyTower};
+
+/// BLAKE2b-512 through the derive macro
+#[derive(Clone, Debug, PartialEq, Eq, Digest)]
+#[digest(hasher = Blake2b512, algorithm = "blake2b-512")]
+struct Blake2bDigest;
+
+#[test]
+fn test_derived_digest_matches_hasher() {
+    assert_eq!(Blake2bDigest::ALGORITHM, "blake2b-512");
+    assert_eq!(Blake2bDigest::OUTPUT_LEN, Some(64));
+    assert_eq!(
+        Blake2bDigest::digest_item(&b"item"),
+        Blake2b512::digest(b"item")
+    );
+
+    let mut hasher = Blake2b512::new();
+    hasher.update(b"a");
+    hasher.update(b"b");
+    assert_eq!(
+        Blake2bDigest::digest_items(&[b"a", b"b"]),
+        hasher.finalize()
+    );
+}
+
+#[test]
+fn test_tower_with_derived_digest() {
+    let mut tower: LazyTower<Vec<u8>, Blake2bDigest> = LazyTower::new(4).unwrap();
+    for i in 0..20u8 {
+        tower.append(vec![i; 8]);
+    }
+
+    assert_eq!(tower.root_digest().unwrap().len(), 64);
+    for index in 0..16 {
+        assert!(tower.generate_proof(index).unwrap().verify());
+    }
+}
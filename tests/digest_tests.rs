@@ -1,6 +1,7 @@
 //! Tests for digest computation
 
-use lazytower_rs::{Digest, LazyTower, TowerNode};
+use lazytower_rs::digest::domain::{BigEndian, DomainSeparated, DomainTag, LittleEndian, Tagged};
+use lazytower_rs::{Digest, LazyTower, PathElement, ProofPath, TowerNode};
 
 /// Test item that can be converted to bytes
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -185,6 +186,7 @@ fn test_digest_trait_implementation() {
     ];
     let multi_digest = TrackedDigest::digest_items(&items);
     assert_eq!(multi_digest.0, "D[a,b,c]");
+    assert_eq!(TrackedDigest::algorithm_id(), "unknown");
 }
 
 #[cfg(feature = "sha256")]
@@ -212,4 +214,383 @@ mod sha256_tests {
             _ => panic!("Expected digest node at level 1"),
         }
     }
+
+    #[test]
+    fn test_incremental_sha256_matches_slice_based_roots() {
+        use lazytower_rs::digest::incremental::Incremental;
+        use lazytower_rs::digest::sha256::Sha256Incremental;
+
+        type Streamed = Incremental<Sha256Incremental>;
+        assert_eq!(Streamed::ALGORITHM, "sha256");
+
+        let mut slices: LazyTower<TestItem, Sha256Digest> = LazyTower::new(3).unwrap();
+        let mut streamed: LazyTower<TestItem, Streamed> = LazyTower::new(3).unwrap();
+        for i in 0..40 {
+            let item = TestItem(format!("item{}", i));
+            slices.append(item.clone());
+            streamed.append(item);
+            assert_eq!(slices.root_digest(), streamed.root_digest());
+        }
+
+        let proof = streamed.generate_proof(7).unwrap();
+        assert_eq!(
+            proof.path.encode(),
+            slices.generate_proof(7).unwrap().path.encode()
+        );
+    }
+
+    #[test]
+    fn test_wrapped_sha256_algorithm_id_in_exported_bundle() {
+        fn bundle_algorithm<D: Digest>() -> String {
+            let mut tower: LazyTower<TestItem, D> = LazyTower::new(2).unwrap();
+            for item in ["a", "b", "c", "d"] {
+                tower.append(TestItem(item.to_string()));
+            }
+            tower.export_proof_bundle(2).unwrap().algorithm
+        }
+
+        assert_eq!(
+            bundle_algorithm::<Tagged<SignatureTag, Sha256Digest>>(),
+            "sha256"
+        );
+        assert_eq!(
+            bundle_algorithm::<DomainSeparated<Sha256Digest>>(),
+            "sha256"
+        );
+    }
+
+    #[test]
+    fn test_wrapped_digests_keep_output_len() {
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        struct Purpose;
+
+        impl DomainTag for Purpose {
+            const TAG: &'static [u8] = b"purpose";
+        }
+
+        assert_eq!(Tagged::<Purpose, Sha256Digest>::OUTPUT_LEN, Some(32));
+        assert_eq!(DomainSeparated::<Sha256Digest>::OUTPUT_LEN, Some(32));
+    }
+
+    #[test]
+    fn test_sha256_algorithm_id_in_exported_bundle() {
+        assert_eq!(Sha256Digest::algorithm_id(), "sha256");
+
+        let mut tower: LazyTower<TestItem, Sha256Digest> = LazyTower::new(2).unwrap();
+        for item in ["a", "b", "c", "d"] {
+            tower.append(TestItem(item.to_string()));
+        }
+        let bundle = tower.export_proof_bundle(2).unwrap();
+        assert_eq!(bundle.algorithm, "sha256");
+    }
+
+    #[test]
+    fn test_dual_digest_commits_to_both_halves() {
+        use lazytower_rs::digest::dual::Dual;
+
+        type Both = Dual<Sha256Digest, TrackedDigest>;
+
+        // Each half matches a single-digest tower, including after digests
+        // of digests have been taken
+        for count in [3, 4, 5, 16, 21, 64] {
+            let mut dual: LazyTower<TestItem, Both> = LazyTower::new(4).unwrap();
+            let mut sha: LazyTower<TestItem, Sha256Digest> = LazyTower::new(4).unwrap();
+            let mut tracked: LazyTower<TestItem, TrackedDigest> = LazyTower::new(4).unwrap();
+            for i in 0..count {
+                let item = TestItem(format!("item{}", i));
+                dual.append(item.clone());
+                sha.append(item.clone());
+                tracked.append(item);
+            }
+
+            let root = dual.root_digest().unwrap();
+            assert_eq!(Some(root.first()), sha.root_digest().as_ref());
+            assert_eq!(Some(root.second()), tracked.root_digest().as_ref());
+            assert_eq!(&root.as_ref()[..32], root.first().as_slice());
+            assert_eq!(&root.as_ref()[32..], root.second().as_ref());
+        }
+
+        let mut dual: LazyTower<TestItem, Both> = LazyTower::new(4).unwrap();
+        for i in 0..16 {
+            dual.append(TestItem(format!("item{}", i)));
+        }
+        for index in 0..16 {
+            let proof = dual.generate_proof(index).unwrap();
+            assert!(proof.verify());
+            assert!(dual.verify(&proof));
+        }
+
+        let mut forged = dual.generate_proof(5).unwrap();
+        forged.item = TestItem("forged".to_string());
+        assert!(!forged.verify());
+    }
+}
+
+#[cfg(feature = "shake")]
+mod shake_tests {
+    use super::*;
+    use lazytower_rs::digest::shake::Shake256Digest;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn test_shake256_known_answers() {
+        assert_eq!(
+            hex(&Shake256Digest::<32>::digest_item(&b"")),
+            "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f"
+        );
+        assert_eq!(
+            hex(&Shake256Digest::<64>::digest_item(&b"abc")),
+            "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739\
+             d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4"
+        );
+
+        // The shorter output is a prefix of the longer one
+        let short = Shake256Digest::<32>::digest_items(&[&b"a"[..], b"bc"]);
+        let long = Shake256Digest::<64>::digest_item(&b"abc");
+        assert_eq!(short[..], long[..32]);
+    }
+
+    fn build_and_prove<const N: usize>() {
+        let mut tower: LazyTower<TestItem, Shake256Digest<N>> = LazyTower::new(3).unwrap();
+        for i in 0..20 {
+            tower.append(TestItem(format!("item{}", i)));
+        }
+        assert_eq!(tower.root_digest().unwrap().len(), N);
+        for index in [0, 8, 17] {
+            assert!(tower.generate_proof(index).unwrap().verify());
+        }
+    }
+
+    #[test]
+    fn test_shake256_towers_of_each_length() {
+        build_and_prove::<32>();
+        build_and_prove::<64>();
+    }
+}
+
+// ===== Domain Separation Tests =====
+
+/// Root of a width-2 tower built from `items`
+fn root_of<D: Digest>(items: &[&str]) -> D::Output {
+    let mut tower: LazyTower<TestItem, D> = LazyTower::new(2).unwrap();
+    for item in items {
+        tower.append(TestItem(item.to_string()));
+    }
+    tower.root_digest().unwrap()
+}
+
+#[test]
+fn test_little_endian_root_matches_hand_computed_framing() {
+    let root = root_of::<DomainSeparated<TrackedDigest, LittleEndian>>(&["A", "B"]);
+
+    let mut framed = vec![0x01, 2, 0, 0, 0, 0, 0, 0, 0];
+    framed.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0, b'A']);
+    framed.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0, b'B']);
+    assert_eq!(root, TrackedDigest::digest_item(&framed));
+}
+
+#[test]
+fn test_big_endian_root_matches_hand_computed_framing() {
+    let root = root_of::<DomainSeparated<TrackedDigest, BigEndian>>(&["A", "B"]);
+
+    let mut framed = vec![0x01, 0, 0, 0, 0, 0, 0, 0, 2];
+    framed.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1, b'A']);
+    framed.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1, b'B']);
+    assert_eq!(root, TrackedDigest::digest_item(&framed));
+}
+
+#[test]
+fn test_endianness_changes_root() {
+    let items = ["A", "B", "C", "D", "E"];
+    let little = root_of::<DomainSeparated<TrackedDigest, LittleEndian>>(&items);
+    let big = root_of::<DomainSeparated<TrackedDigest, BigEndian>>(&items);
+    assert_ne!(little, big);
+}
+
+#[test]
+fn test_domain_separated_single_item_framing() {
+    let digest = DomainSeparated::<TrackedDigest>::digest_item(&"A");
+    assert_eq!(
+        digest,
+        TrackedDigest::digest_item(&[0x00, 1, 0, 0, 0, 0, 0, 0, 0, b'A'])
+    );
+}
+
+#[test]
+fn test_domain_separated_proofs_verify() {
+    let mut tower: LazyTower<TestItem, DomainSeparated<TrackedDigest, BigEndian>> =
+        LazyTower::new(2).unwrap();
+    for i in 0..4 {
+        tower.append(TestItem(i.to_string()));
+    }
+
+    for i in 0..4 {
+        assert!(tower.generate_proof(i).unwrap().verify());
+    }
+}
+
+// ===== Domain Tag Tests =====
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SignatureTag;
+
+impl DomainTag for SignatureTag {
+    const TAG: &'static [u8] = b"signature";
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct AuditTag;
+
+impl DomainTag for AuditTag {
+    const TAG: &'static [u8] = b"audit";
+}
+
+#[test]
+fn test_tagged_digest_prepends_tag() {
+    assert_eq!(
+        Tagged::<AuditTag, TrackedDigest>::digest_item(&"A"),
+        TrackedDigest::digest_item(&"auditA")
+    );
+    assert_eq!(
+        Tagged::<AuditTag, TrackedDigest>::digest_items(&["A", "B"]),
+        TrackedDigest::digest_items(&["audit", "A", "B"])
+    );
+}
+
+#[test]
+fn test_different_tags_produce_different_roots_and_proofs() {
+    let items = ["A", "B", "C", "D"];
+    let signature_root = root_of::<Tagged<SignatureTag, TrackedDigest>>(&items);
+    let audit_root = root_of::<Tagged<AuditTag, TrackedDigest>>(&items);
+    assert_ne!(signature_root, audit_root);
+
+    let mut tower: LazyTower<TestItem, Tagged<SignatureTag, TrackedDigest>> =
+        LazyTower::new(2).unwrap();
+    for item in items {
+        tower.append(TestItem(item.to_string()));
+    }
+    let proof = tower.generate_proof(1).unwrap();
+    assert!(proof.verify());
+
+    // Reinterpret the same path under the audit tag
+    let mut audit_path = ProofPath::<Tagged<AuditTag, TrackedDigest>>::new();
+    for element in &proof.path.elements {
+        match element {
+            PathElement::Siblings { position, siblings } => {
+                audit_path.add_siblings(*position, siblings.clone())
+            }
+            PathElement::RawSiblings { position, siblings } => {
+                audit_path.add_raw_siblings(*position, siblings.clone())
+            }
+        }
+    }
+
+    assert!(!audit_path.verify(&proof.item, &proof.root));
+    assert!(!audit_path.verify(&proof.item, &audit_root));
+}
+
+/// Digest with a batch override that formats every item in one pass
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BatchedDigest;
+
+impl Digest for BatchedDigest {
+    type Output = TrackedDigestOutput;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        TrackedDigest::digest_item(item)
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        TrackedDigest::digest_items(items)
+    }
+
+    fn digest_batch(items: &[&[u8]]) -> Vec<Self::Output> {
+        let formatted: Vec<String> = items
+            .iter()
+            .map(|item| format!("D({})", String::from_utf8_lossy(item)))
+            .collect();
+        formatted.into_iter().map(TrackedDigestOutput).collect()
+    }
+}
+
+#[test]
+fn test_digest_batch_default_and_override_agree() {
+    let items: Vec<&[u8]> = vec![b"A", b"BC", b"", b"DEF"];
+
+    let default = TrackedDigest::digest_batch(&items);
+    let batched = BatchedDigest::digest_batch(&items);
+
+    assert_eq!(default, batched);
+    assert_eq!(default.len(), items.len());
+    for (item, digest) in items.iter().zip(&default) {
+        assert_eq!(digest, &TrackedDigest::digest_item(item));
+    }
+}
+
+#[test]
+fn test_ct_eq_agrees_with_eq() {
+    let a = TrackedDigest::digest_item(&b"left");
+    let b = TrackedDigest::digest_item(&b"left");
+    let c = TrackedDigest::digest_item(&b"right");
+    let shorter = TrackedDigest::digest_item(&b"l");
+
+    for (x, y) in [(&a, &b), (&a, &c), (&c, &a), (&a, &shorter), (&a, &a)] {
+        assert_eq!(TrackedDigest::ct_eq(x, y), x == y);
+    }
+
+    // A proof still verifies only against its own root
+    let path = ProofPath::<TrackedDigest>::new();
+    assert!(path.verify(&b"left", &a));
+    assert!(!path.verify(&b"left", &c));
+}
+
+/// Digest whose root comparison ignores ASCII case, to observe `ct_eq` use
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CaseBlindDigest;
+
+impl Digest for CaseBlindDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        [b"d(", item.as_ref(), b")"].concat()
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let items: Vec<&[u8]> = items.iter().map(|item| item.as_ref()).collect();
+        [b"d[", items.join(&b","[..]).as_slice(), b"]"].concat()
+    }
+
+    fn ct_eq(a: &Self::Output, b: &Self::Output) -> bool {
+        a.eq_ignore_ascii_case(b)
+    }
+}
+
+#[test]
+fn test_every_verifier_compares_roots_with_ct_eq() {
+    let mut tower: LazyTower<Vec<u8>, CaseBlindDigest> = LazyTower::new(2).unwrap();
+    for item in ["a", "b", "c", "d"] {
+        tower.append(item.as_bytes().to_vec());
+    }
+    let proof = tower.generate_proof(2).unwrap();
+    let shouted = proof.root.to_ascii_uppercase();
+    assert_ne!(shouted, proof.root);
+
+    let mut scratch = Vec::new();
+    assert!(proof.path.verify(&proof.item, &shouted));
+    assert!(proof.path.verify_into(&proof.item, &shouted, &mut scratch));
+    assert!(ProofPath::<CaseBlindDigest>::new().verify_into(
+        &b"x",
+        &b"D(X)".to_vec(),
+        &mut scratch
+    ));
+
+    // A lone leaf bound to metadata is compared with the root directly
+    let mut lone: LazyTower<Vec<u8>, CaseBlindDigest> = LazyTower::new(2).unwrap();
+    lone.append_with_meta(b"only".to_vec(), 7);
+    let mut proof = lone.generate_proof(0).unwrap();
+    proof.root = proof.root.to_ascii_uppercase();
+    assert!(proof.verify());
 }
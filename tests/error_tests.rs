@@ -1,6 +1,6 @@
 //! Tests for error handling
 
-use lazytower_rs::{Digest, LazyTower, LazyTowerError};
+use lazytower_rs::{Digest, LazyTower, LazyTowerError, TowerOptions};
 
 /// Mock digest for testing
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -90,3 +90,336 @@ fn test_generate_proof_index_out_of_bounds() {
         _ => panic!("Expected InvalidIndex error for out of bounds index"),
     }
 }
+
+#[test]
+fn test_empty_item_accepted_by_default() {
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::new(4).unwrap();
+    assert!(!tower.options().reject_empty_items);
+
+    assert!(tower.try_append(Vec::new()).is_ok());
+    tower.append(Vec::new());
+    assert_eq!(tower.len(), 2);
+}
+
+#[test]
+fn test_empty_item_rejected_when_strict() {
+    let options = TowerOptions {
+        reject_empty_items: true,
+        ..TowerOptions::default()
+    };
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::with_options(4, options).unwrap();
+
+    assert_eq!(tower.try_append(Vec::new()), Err(LazyTowerError::EmptyItem));
+    assert!(tower.is_empty());
+
+    assert!(tower.try_append(vec![1]).is_ok());
+    assert_eq!(tower.len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Empty items are rejected")]
+fn test_append_panics_on_rejected_empty_item() {
+    let options = TowerOptions {
+        reject_empty_items: true,
+        ..TowerOptions::default()
+    };
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::with_options(4, options).unwrap();
+    tower.append(Vec::new());
+}
+
+#[test]
+fn test_max_item_bytes_limits_item_size() {
+    let options = TowerOptions {
+        max_item_bytes: Some(4),
+        ..TowerOptions::default()
+    };
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::with_options(2, options).unwrap();
+
+    assert!(tower.try_append(vec![1, 2, 3]).is_ok());
+    assert!(tower.try_append(vec![1, 2, 3, 4]).is_ok());
+    assert_eq!(
+        tower.try_append(vec![0; 7]),
+        Err(LazyTowerError::ItemTooLarge { size: 7, max: 4 })
+    );
+    assert_eq!(tower.len(), 2);
+    assert_eq!(
+        LazyTowerError::ItemTooLarge { size: 7, max: 4 }.to_string(),
+        "Item of 7 bytes exceeds the 4 byte limit"
+    );
+
+    // The default tower accepts any size
+    let mut unbounded = LazyTower::<Vec<u8>, MockDigest>::new(2).unwrap();
+    assert!(unbounded.try_append(vec![0; 1 << 16]).is_ok());
+}
+
+#[test]
+#[should_panic(expected = "exceeds the 2 byte limit")]
+fn test_append_panics_on_oversized_item() {
+    let options = TowerOptions {
+        max_item_bytes: Some(2),
+        ..TowerOptions::default()
+    };
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::with_options(2, options).unwrap();
+    tower.append(vec![1, 2, 3]);
+}
+
+#[test]
+fn test_validator_rejects_long_items() {
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::new(2).unwrap();
+    tower.set_validator(|item: &Vec<u8>| {
+        if item.len() > 4 {
+            Err(format!("{} bytes is longer than 4", item.len()))
+        } else {
+            Ok(())
+        }
+    });
+
+    assert!(tower.try_append(vec![1, 2, 3, 4]).is_ok());
+    let root_before = tower.root_digest();
+    assert_eq!(
+        tower.try_append(vec![0; 6]),
+        Err(LazyTowerError::ValidationFailed {
+            reason: "6 bytes is longer than 4".to_string()
+        })
+    );
+    assert_eq!(tower.len(), 1);
+    assert_eq!(tower.root_digest(), root_before);
+    assert_eq!(
+        tower.append_batch_atomic(vec![vec![5], vec![0; 5]]),
+        Err(LazyTowerError::ValidationFailed {
+            reason: "5 bytes is longer than 4".to_string()
+        })
+    );
+    assert_eq!(tower.len(), 1);
+    assert!(tower.validate().is_ok());
+    assert_eq!(
+        LazyTowerError::ValidationFailed {
+            reason: "too long".to_string()
+        }
+        .to_string(),
+        "Item rejected by validator: too long"
+    );
+}
+
+#[test]
+fn test_item_edits_are_checked_before_write_back() {
+    let options = TowerOptions {
+        max_item_bytes: Some(8),
+        ..TowerOptions::default()
+    };
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::with_options(2, options).unwrap();
+    tower.set_validator(|item: &Vec<u8>| {
+        if item.len() > 4 {
+            Err(format!("{} bytes is longer than 4", item.len()))
+        } else {
+            Ok(())
+        }
+    });
+    for i in 0..4u8 {
+        tower.append(vec![i]);
+    }
+    let root_before = tower.root_digest();
+
+    // A rejected edit is discarded on drop
+    tower.get_mut(1).unwrap().extend_from_slice(&[0; 5]);
+    assert_eq!(tower.get(1), Some(&vec![1]));
+    assert_eq!(tower.root_digest(), root_before);
+
+    // and reported by commit
+    let mut guard = tower.get_mut(1).unwrap();
+    guard.extend_from_slice(&[0; 9]);
+    assert_eq!(
+        guard.commit(),
+        Err(LazyTowerError::ItemTooLarge { size: 10, max: 8 })
+    );
+    let mut guard = tower.get_mut(2).unwrap();
+    guard.extend_from_slice(&[0; 4]);
+    assert_eq!(
+        guard.commit(),
+        Err(LazyTowerError::ValidationFailed {
+            reason: "5 bytes is longer than 4".to_string()
+        })
+    );
+    assert_eq!(tower.root_digest(), root_before);
+    assert!(tower.validate().is_ok());
+
+    let mut guard = tower.get_mut(2).unwrap();
+    guard.push(7);
+    assert_eq!(guard.commit(), Ok(()));
+    assert_eq!(tower.get(2), Some(&vec![2, 7]));
+    assert_ne!(tower.root_digest(), root_before);
+}
+
+#[test]
+#[should_panic(expected = "Item rejected by validator: empty")]
+fn test_append_panics_on_rejected_item() {
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::new(2).unwrap();
+    tower.set_validator(|item: &Vec<u8>| {
+        if item.is_empty() {
+            Err("empty".to_string())
+        } else {
+            Ok(())
+        }
+    });
+    tower.append(Vec::new());
+}
+
+#[test]
+fn test_atomic_batch_with_empty_item_leaves_tower_unchanged() {
+    let options = TowerOptions {
+        reject_empty_items: true,
+        ..TowerOptions::default()
+    };
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::with_options(2, options).unwrap();
+    assert_eq!(
+        tower.append_batch_atomic(vec![vec![1], vec![2], vec![3]]),
+        Ok(vec![0, 1, 2])
+    );
+    let root_before = tower.root_digest();
+
+    let batch = vec![vec![4], vec![5], Vec::new(), vec![6]];
+    assert_eq!(
+        tower.append_batch_atomic(batch),
+        Err(LazyTowerError::EmptyItem)
+    );
+
+    assert_eq!(tower.len(), 3);
+    assert_eq!(tower.root_digest(), root_before);
+    assert_eq!(tower.get(3), None);
+    assert!(tower.validate().is_ok());
+
+    assert_eq!(tower.append_batch_atomic(vec![vec![4]]), Ok(vec![3]));
+}
+
+/// Digest declaring 8-byte outputs but echoing the length of its input
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct VariableLengthDigest;
+
+impl Digest for VariableLengthDigest {
+    type Output = Vec<u8>;
+
+    const OUTPUT_LEN: Option<usize> = Some(8);
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut output = vec![0xd1; 8];
+        output.extend_from_slice(item.as_ref());
+        output.truncate(8);
+        output
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        items
+            .iter()
+            .flat_map(|item| item.as_ref().iter().copied())
+            .collect()
+    }
+}
+
+#[test]
+fn test_try_append_rejects_digest_of_wrong_length() {
+    let mut tower = LazyTower::<Vec<u8>, VariableLengthDigest>::new(2).unwrap();
+    assert!(tower.try_append(vec![1, 2, 3, 4]).is_ok());
+    let root_before = tower.root_digest();
+
+    // The second item overflows level 0 into a 7-byte digest
+    assert_eq!(
+        tower.try_append(vec![5, 6, 7]),
+        Err(LazyTowerError::DigestLengthMismatch {
+            expected: 8,
+            actual: 7
+        })
+    );
+    assert_eq!(tower.len(), 1);
+    assert_eq!(tower.root_digest(), root_before);
+    assert!(tower.validate().is_ok());
+
+    // Groups that happen to hash to 8 bytes are accepted
+    assert!(tower.try_append(vec![5, 6, 7, 8]).is_ok());
+    assert_eq!(tower.len(), 2);
+
+    // Digest leaves are checked before they enter level 0
+    assert_eq!(
+        tower.append_digest(vec![0; 3]),
+        Err(LazyTowerError::DigestLengthMismatch {
+            expected: 8,
+            actual: 3
+        })
+    );
+    assert_eq!(tower.len(), 2);
+    assert_eq!(
+        LazyTowerError::DigestLengthMismatch {
+            expected: 8,
+            actual: 7
+        }
+        .to_string(),
+        "Digest output of 7 bytes where 8 were expected"
+    );
+}
+
+#[test]
+fn test_batches_reject_digest_of_wrong_length_mid_batch() {
+    type Append = fn(
+        &mut LazyTower<Vec<u8>, VariableLengthDigest>,
+        Vec<Vec<u8>>,
+    ) -> Result<Vec<usize>, LazyTowerError>;
+    let appends: Vec<Append> = vec![LazyTower::append_batch_atomic, |tower, items| {
+        tower.append_batch_with_progress(items, |_, _| {})
+    }];
+    #[cfg(feature = "rayon")]
+    let appends = [appends, vec![LazyTower::append_batch_par as Append]].concat();
+
+    for append in appends {
+        let mut tower = LazyTower::<Vec<u8>, VariableLengthDigest>::new(2).unwrap();
+        tower.append(vec![1, 2, 3, 4]);
+        let root_before = tower.root_digest();
+
+        // The first item completes an 8-byte group, the third a 7-byte one
+        let batch = vec![vec![5, 6, 7, 8], vec![1, 2, 3], vec![4, 5, 6, 7]];
+        assert_eq!(
+            append(&mut tower, batch),
+            Err(LazyTowerError::DigestLengthMismatch {
+                expected: 8,
+                actual: 7
+            })
+        );
+        assert_eq!(tower.len(), 1);
+        assert_eq!(tower.root_digest(), root_before);
+        assert!(tower.validate().is_ok());
+
+        let batch = vec![vec![5, 6, 7, 8], vec![1, 2, 3, 4]];
+        assert_eq!(append(&mut tower, batch), Ok(vec![1, 2]));
+    }
+}
+
+#[test]
+fn test_flush_and_item_edits_reject_digest_of_wrong_length() {
+    let mut tower = LazyTower::<Vec<u8>, VariableLengthDigest>::new(3).unwrap();
+    tower.append(vec![1, 2, 3, 4]);
+    tower.append(vec![5, 6, 7]);
+    let root_before = tower.root_digest();
+
+    // Flushing the two items would store a 7-byte digest
+    assert_eq!(
+        tower.try_flush(),
+        Err(LazyTowerError::DigestLengthMismatch {
+            expected: 8,
+            actual: 7
+        })
+    );
+    assert_eq!(tower.level(0).unwrap().len(), 2);
+    assert_eq!(tower.root_digest(), root_before);
+
+    let mut tower = LazyTower::<Vec<u8>, VariableLengthDigest>::new(2).unwrap();
+    tower.append(vec![1, 2, 3, 4]);
+    tower.append(vec![5, 6, 7, 8]);
+    let root_before = tower.root_digest();
+
+    // An edit that would change the overflow digest's length is discarded
+    *tower.get_mut(0).unwrap() = vec![1, 2, 3];
+    assert_eq!(tower.get(0), Some(&vec![1, 2, 3, 4]));
+    assert_eq!(tower.root_digest(), root_before);
+    assert!(tower.validate().is_ok());
+
+    *tower.get_mut(0).unwrap() = vec![9, 9, 9, 9];
+    assert_eq!(tower.root_digest(), Some(vec![9, 9, 9, 9, 5, 6, 7, 8]));
+}
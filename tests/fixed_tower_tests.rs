@@ -0,0 +1,133 @@
+//! Tests for LazyTowerFixed
+
+use lazytower_rs::{Digest, LazyTower, LazyTowerFixed};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// System allocator counting allocations made on the current thread
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Count the allocations `f` makes on this thread
+fn allocations_in(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+/// XOR-folding digest that never allocates
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct XorDigest;
+
+impl Digest for XorDigest {
+    type Output = [u8; 32];
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut out: [u8; 32] = [0x5a; 32];
+        for (i, byte) in item.as_ref().iter().enumerate() {
+            out[i % 32] = out[i % 32].rotate_left(3) ^ byte;
+        }
+        out
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut out: [u8; 32] = [0xa5; 32];
+        for (n, item) in items.iter().enumerate() {
+            for (i, byte) in item.as_ref().iter().enumerate() {
+                out[i % 32] = out[i % 32].rotate_left(1) ^ byte ^ n as u8;
+            }
+        }
+        out
+    }
+}
+
+fn item(i: u8) -> [u8; 32] {
+    [i; 32]
+}
+
+#[test]
+fn test_fixed_items_prove_and_verify() {
+    let mut tower = LazyTowerFixed::<32, XorDigest>::new(4).unwrap();
+    for i in 0..16 {
+        tower.append(item(i));
+    }
+    assert_eq!(tower.len(), 16);
+    assert_eq!(tower.get(3), Some(&item(3)));
+
+    let mut plain = LazyTower::<Vec<u8>, XorDigest>::new(4).unwrap();
+    for i in 0..16 {
+        plain.append(item(i).to_vec());
+    }
+    assert_eq!(tower.root_digest(), plain.root_digest());
+
+    for index in [0, 7, 15] {
+        let proof = tower.generate_proof(index).unwrap();
+        assert_eq!(proof.item, item(index as u8));
+        assert!(tower.verify(&proof));
+        assert!(proof.verify());
+    }
+    assert!(LazyTowerFixed::<32, XorDigest>::new(1).is_err());
+}
+
+#[test]
+fn test_fixed_items_allocation_bounds() {
+    const COUNT: usize = 1024;
+    // Items live inline; the tower's maps, levels and overflow records are
+    // what still allocate on append
+    const APPEND_ALLOCATIONS_PER_ITEM: usize = 4;
+
+    let mut tower = LazyTowerFixed::<32, XorDigest>::new(4).unwrap();
+    let appended = allocations_in(|| {
+        for i in 0..COUNT {
+            tower.append(item(i as u8));
+        }
+    });
+    assert!(
+        appended <= APPEND_ALLOCATIONS_PER_ITEM * COUNT,
+        "{} allocations for {} appends",
+        appended,
+        COUNT
+    );
+
+    for index in 0..COUNT {
+        let mut proof = None;
+        let generated = allocations_in(|| proof = tower.generate_proof(index).ok());
+        let proof = proof.unwrap();
+        let levels = proof.path.elements.len();
+
+        // Proving and verifying allocate per level, never per tower item
+        assert!(
+            generated <= 2 * levels + 2,
+            "item {}: {} allocations to prove {} levels",
+            index,
+            generated,
+            levels
+        );
+
+        let verified = allocations_in(|| assert!(tower.verify(&proof)));
+        assert!(
+            verified <= levels + 1,
+            "item {}: {} allocations to verify {} levels",
+            index,
+            verified,
+            levels
+        );
+    }
+}
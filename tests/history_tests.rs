@@ -0,0 +1,155 @@
+//! Tests for root history tracking
+
+use lazytower_rs::{Digest, LazyTower};
+
+/// Mock digest for testing
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockDigest;
+
+impl Digest for MockDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut result = b"digest(".to_vec();
+        result.extend_from_slice(item.as_ref());
+        result.extend_from_slice(b")");
+        result
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut result = b"digest_items[".to_vec();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                result.extend_from_slice(b",");
+            }
+            result.extend_from_slice(item.as_ref());
+        }
+        result.extend_from_slice(b"]");
+        result
+    }
+}
+
+#[test]
+fn test_history_disabled_by_default() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    tower.append(b"A".to_vec());
+
+    assert!(!tower.is_history_enabled());
+    assert_eq!(tower.history().count(), 0);
+}
+
+#[test]
+fn test_history_records_root_after_each_append() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    tower.enable_history();
+
+    let mut expected_roots = Vec::new();
+    for i in 0..5 {
+        tower.append(vec![b'0' + i]);
+        expected_roots.push(tower.root_digest().unwrap());
+    }
+
+    let history: Vec<(usize, &Vec<u8>)> = tower.history().collect();
+    assert_eq!(history.len(), 5);
+
+    for (i, (count, root)) in history.iter().enumerate() {
+        assert_eq!(*count, i + 1);
+        assert_eq!(*root, &expected_roots[i]);
+    }
+}
+
+#[test]
+fn test_shrink_history_keeps_newest_entries() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    tower.enable_history();
+
+    for i in 0..5 {
+        tower.append(vec![b'0' + i]);
+    }
+    tower.shrink_history(2);
+
+    let counts: Vec<usize> = tower.history().map(|(count, _)| count).collect();
+    assert_eq!(counts, vec![4, 5]);
+    assert_eq!(
+        tower.history().last().unwrap().1,
+        &tower.root_digest().unwrap()
+    );
+}
+
+#[cfg(feature = "test-utils")]
+#[test]
+fn test_root_collision_hook_fires_on_crafted_collision() {
+    use lazytower_rs::digest::mock::IdentityDigest;
+    use std::sync::{Arc, Mutex};
+
+    let collisions = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&collisions);
+
+    let mut tower: LazyTower<Vec<u8>, IdentityDigest> = LazyTower::new(2).unwrap();
+    tower.on_root_collision(move |root: &Vec<u8>, earlier, now| {
+        seen.lock().unwrap().push((root.clone(), earlier, now));
+    });
+    assert!(tower.is_history_enabled());
+
+    // ["ab"] and ["ab", ""] concatenate to the same bytes
+    tower.append(b"ab".to_vec());
+    assert!(collisions.lock().unwrap().is_empty());
+    tower.append(Vec::new());
+
+    assert_eq!(*collisions.lock().unwrap(), vec![(b"ab".to_vec(), 1, 2)]);
+}
+
+#[cfg(feature = "test-utils")]
+#[test]
+fn test_root_collision_hook_forgets_shrunk_history() {
+    use lazytower_rs::digest::mock::IdentityDigest;
+
+    let mut tower: LazyTower<Vec<u8>, IdentityDigest> = LazyTower::new(2).unwrap();
+    tower.on_root_collision(|_: &Vec<u8>, _, _| panic!("collision with a dropped root"));
+
+    tower.append(b"ab".to_vec());
+    tower.shrink_history(0);
+    tower.append(Vec::new());
+
+    let counts: Vec<usize> = tower.history().map(|(count, _)| count).collect();
+    assert_eq!(counts, vec![2]);
+}
+
+#[test]
+fn test_root_collision_hook_ignores_unchanged_top_level() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    tower.on_root_collision(|_: &Vec<u8>, _, _| panic!("unexpected root collision"));
+
+    // The root repeats while items 3 and 5 wait below the top level
+    for i in 0..6 {
+        tower.append(vec![b'0' + i]);
+    }
+    let roots: Vec<&Vec<u8>> = tower.history().map(|(_, root)| root).collect();
+    assert_eq!(roots[1], roots[2]);
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn test_root_collision_hook_never_fires_with_sha256() {
+    use lazytower_rs::digest::sha256::Sha256Digest;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let fired = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&fired);
+
+    let mut tower: LazyTower<Vec<u8>, Sha256Digest> = LazyTower::new(3).unwrap();
+    tower.on_root_collision(move |_: &[u8; 32], _, _| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    for i in 0..500u32 {
+        tower.append(i.to_le_bytes().to_vec());
+    }
+    // Empty and repeated items must not trip the hook either
+    tower.append(Vec::new());
+    tower.append(0u32.to_le_bytes().to_vec());
+
+    assert_eq!(tower.history().count(), 502);
+    assert_eq!(fired.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,130 @@
+//! Tests for the key-value tower wrapper
+
+use lazytower_rs::{Digest, KeyedTower};
+
+/// Mock digest for testing
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockDigest;
+
+impl Digest for MockDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut result = b"digest(".to_vec();
+        result.extend_from_slice(item.as_ref());
+        result.extend_from_slice(b")");
+        result
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut result = b"digest_items[".to_vec();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                result.extend_from_slice(b",");
+            }
+            result.extend_from_slice(item.as_ref());
+        }
+        result.extend_from_slice(b"]");
+        result
+    }
+}
+
+/// Digest that hashes its inputs as one unframed stream, like most real
+/// hash functions
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ConcatDigest;
+
+impl Digest for ConcatDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut result = b"item:".to_vec();
+        result.extend_from_slice(item.as_ref());
+        result
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut result = b"items:".to_vec();
+        for item in items {
+            result.extend_from_slice(item.as_ref());
+        }
+        result
+    }
+}
+
+fn build_tower(pairs: usize) -> KeyedTower<String, String, MockDigest> {
+    let mut tower = KeyedTower::new(3).unwrap();
+    for i in 0..pairs {
+        tower
+            .append(format!("key{}", i), format!("value{}", i))
+            .unwrap();
+    }
+    tower
+}
+
+#[test]
+fn test_prove_key_value() {
+    let tower = build_tower(9);
+    assert_eq!(tower.len(), 9);
+    assert_eq!(tower.get(&"key4".to_string()), Some(&"value4".to_string()));
+
+    let proof = tower.prove_key(&"key4".to_string()).unwrap();
+    assert_eq!(proof.value, "value4");
+    assert_eq!(Some(&proof.proof.root), tower.root_digest().as_ref());
+    assert!(proof.verify());
+    assert!(tower.verify(&proof));
+
+    assert!(tower.prove_key(&"missing".to_string()).is_none());
+}
+
+#[test]
+fn test_keyed_proof_rejects_wrong_value() {
+    let tower = build_tower(9);
+    let mut proof = tower.prove_key(&"key4".to_string()).unwrap();
+    proof.value = "value5".to_string();
+    assert!(!proof.verify());
+    assert!(!tower.verify(&proof));
+
+    // Another key's valid leaf does not prove this key
+    let mut proof = tower.prove_key(&"key5".to_string()).unwrap();
+    proof.key = "key4".to_string();
+    assert!(!proof.verify());
+}
+
+#[test]
+fn test_prove_key_uses_latest_value() {
+    // The ninth pair completes a group at every level, so the root covers it
+    let mut tower = build_tower(8);
+    tower
+        .append("key4".to_string(), "updated".to_string())
+        .unwrap();
+    assert_eq!(tower.get(&"key4".to_string()), Some(&"updated".to_string()));
+
+    let proof = tower.prove_key(&"key4".to_string()).unwrap();
+    assert_eq!(proof.value, "updated");
+    assert!(tower.verify(&proof));
+}
+
+#[test]
+fn test_single_pair_verifies_against_tower() {
+    let mut tower: KeyedTower<String, String, MockDigest> = KeyedTower::new(2).unwrap();
+    tower.append("only".to_string(), "one".to_string()).unwrap();
+    let proof = tower.prove_key(&"only".to_string()).unwrap();
+    assert!(tower.verify(&proof));
+}
+
+#[test]
+fn test_keyed_proof_rejects_shifted_key_boundary() {
+    let mut tower: KeyedTower<String, String, ConcatDigest> = KeyedTower::new(2).unwrap();
+    tower.append("ab".to_string(), "c".to_string()).unwrap();
+    tower.append("x".to_string(), "y".to_string()).unwrap();
+
+    let mut proof = tower.prove_key(&"ab".to_string()).unwrap();
+    assert!(proof.verify());
+
+    // Moving bytes from the key into the value must not keep the leaf
+    proof.key = "a".to_string();
+    proof.value = "bc".to_string();
+    assert!(!proof.verify());
+    assert!(!tower.verify(&proof));
+}
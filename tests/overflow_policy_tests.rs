@@ -0,0 +1,156 @@
+//! Tests for pluggable overflow policies
+
+use lazytower_rs::{Digest, EagerOverflow, LazyOverflow, LazyTower, OverflowPolicy};
+
+/// Mock digest for testing
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockDigest;
+
+impl Digest for MockDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut result = b"digest(".to_vec();
+        result.extend_from_slice(item.as_ref());
+        result.extend_from_slice(b")");
+        result
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut result = b"digest_items[".to_vec();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                result.extend_from_slice(b",");
+            }
+            result.extend_from_slice(item.as_ref());
+        }
+        result.extend_from_slice(b"]");
+        result
+    }
+}
+
+/// Overflow only once a level holds two full groups
+#[derive(Debug)]
+struct DoubleWidthOverflow;
+
+impl OverflowPolicy for DoubleWidthOverflow {
+    fn should_overflow(&self, _level: usize, current_len: usize, width: usize) -> bool {
+        current_len >= 2 * width
+    }
+}
+
+fn item(i: usize) -> Vec<u8> {
+    format!("item{}", i).into_bytes()
+}
+
+#[test]
+fn test_eager_policy_matches_default() {
+    let mut default = LazyTower::<Vec<u8>, MockDigest>::new(3).unwrap();
+    let mut eager =
+        LazyTower::<Vec<u8>, MockDigest>::with_overflow_policy(3, EagerOverflow).unwrap();
+
+    for i in 0..30 {
+        default.append(item(i));
+        eager.append(item(i));
+        assert_eq!(default.root_digest(), eager.root_digest());
+        assert!(eager.validate().is_ok());
+    }
+}
+
+#[test]
+fn test_lazy_policy_defers_overflow_until_flush() {
+    let mut eager = LazyTower::<Vec<u8>, MockDigest>::new(2).unwrap();
+    let mut lazy = LazyTower::<Vec<u8>, MockDigest>::with_overflow_policy(2, LazyOverflow).unwrap();
+    eager.enable_metrics();
+    lazy.enable_metrics();
+
+    for i in 0..11 {
+        eager.append(item(i));
+        lazy.append(item(i));
+    }
+
+    // Everything is still waiting at the bottom level
+    assert_eq!(lazy.height(), 1);
+    assert_eq!(lazy.level(0).unwrap().len(), 11);
+    assert_eq!(lazy.metrics().unwrap().total_overflows, 0);
+    assert!(lazy.validate().is_ok());
+
+    // Flushing either tower groups the same nodes
+    eager.flush();
+    lazy.flush();
+
+    assert_eq!(lazy.root_digest(), eager.root_digest());
+    assert_eq!(
+        lazy.metrics().unwrap().total_overflows,
+        eager.metrics().unwrap().total_overflows
+    );
+    assert!(lazy.validate().is_ok());
+    for index in 0..11 {
+        let proof = lazy.generate_proof(index).unwrap();
+        assert!(proof.verify());
+        assert_eq!(proof.path, eager.generate_proof(index).unwrap().path);
+    }
+}
+
+#[test]
+fn test_custom_policy_converges_after_flush() {
+    let mut eager = LazyTower::<Vec<u8>, MockDigest>::new(3).unwrap();
+    let mut custom =
+        LazyTower::<Vec<u8>, MockDigest>::with_overflow_policy(3, DoubleWidthOverflow).unwrap();
+
+    for i in 0..40 {
+        eager.append(item(i));
+        custom.append(item(i));
+        assert!(custom.validate().is_ok());
+    }
+
+    eager.flush();
+    custom.flush();
+    assert_eq!(custom.root_digest(), eager.root_digest());
+    assert!(custom.validate().is_ok());
+}
+
+#[test]
+fn test_flush_collapses_partial_level() {
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::new(4).unwrap();
+    for i in 0..3 {
+        tower.append(item(i));
+    }
+    let root_before = tower.root_digest();
+
+    tower.flush();
+
+    // The three items now sit under a single digest one level up
+    assert!(tower.level(0).unwrap().is_empty());
+    assert_eq!(tower.level(1).unwrap().len(), 1);
+    assert_eq!(tower.root_digest(), root_before);
+    assert!(tower.validate().is_ok());
+
+    for index in 0..3 {
+        let proof = tower.generate_proof(index).unwrap();
+        assert!(proof.verify());
+        assert_eq!(proof.root, tower.root_digest().unwrap());
+    }
+}
+
+#[test]
+fn test_flush_makes_lone_low_items_provable() {
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::new(4).unwrap();
+    for i in 0..5 {
+        tower.append(item(i));
+    }
+
+    // Item 4 sits alone below the top digest and is not covered by the root
+    assert!(!tower.generate_proof(4).unwrap().verify());
+
+    tower.flush();
+
+    assert_eq!(tower.logical_height(), 3);
+    for index in 0..5 {
+        assert!(tower.generate_proof(index).unwrap().verify());
+    }
+
+    // Appending after a flush keeps the tower consistent
+    tower.append(item(5));
+    assert!(tower.validate().is_ok());
+}
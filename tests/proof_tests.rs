@@ -1,6 +1,10 @@
 //! Tests for proof generation and verification
 
-use lazytower_rs::{Digest, LazyTower, MembershipProof, ProofPath};
+use lazytower_rs::digest::incremental::{Incremental, IncrementalDigest};
+use lazytower_rs::{
+    Digest, LazyTower, LazyTowerError, MembershipProof, PathElement, PathKind, ProofPath,
+    SiblingOrder, TowerOptions,
+};
 
 /// Test item that can be converted to bytes
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -139,6 +143,8 @@ fn test_membership_proof_verify() {
         item: item.clone(),
         path,
         root,
+        meta: None,
+        nonce: None,
     };
 
     assert!(proof.verify());
@@ -541,3 +547,1565 @@ fn test_multi_level_overflow_proof_generation() {
         }
     }
 }
+
+#[test]
+fn test_proof_path_exact_elements_after_multi_level_overflow() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+
+    // Structure: Level 0: [4], Level 2: [H[H[0,1],H[2,3]]]
+    for i in 0..5 {
+        tower.append(vec![b'0' + i]);
+    }
+
+    let proof = tower.generate_proof(2).unwrap();
+
+    let mut expected = ProofPath::<MockDigest>::new();
+    expected.add_raw_siblings(0, vec![b"3".to_vec()]);
+    expected.add_siblings(1, vec![b"digest_items[0,1]".to_vec()]);
+    assert_eq!(proof.path, expected);
+}
+
+// ===== Imported Leaf Digest Tests =====
+
+#[test]
+fn test_proof_generation_from_leaf_digests() {
+    let leaves: Vec<Vec<u8>> = (0..4).map(|i| MockDigest::digest_item(&[i])).collect();
+    let tower: LazyTower<Vec<u8>, MockDigest> =
+        LazyTower::from_leaf_digests(2, leaves.clone()).unwrap();
+
+    assert_eq!(tower.len(), 4);
+    assert!(tower.validate().is_ok());
+
+    let left = MockDigest::digest_items(&[&leaves[0], &leaves[1]]);
+    let right = MockDigest::digest_items(&[&leaves[2], &leaves[3]]);
+    assert_eq!(
+        tower.root_digest().unwrap(),
+        MockDigest::digest_items(&[&left, &right])
+    );
+
+    for (i, leaf) in leaves.iter().enumerate() {
+        let proof = tower.generate_proof(i).unwrap();
+        assert_eq!(&proof.item, leaf);
+        assert!(proof.verify(), "Proof verification failed for leaf {}", i);
+    }
+}
+
+#[test]
+fn test_appended_digests_fold_without_rehashing() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    let first = MockDigest::digest_item(&b"x");
+    let second = MockDigest::digest_item(&b"y");
+
+    tower.append(b"a".to_vec());
+    tower.append_digest(first.clone()).unwrap();
+    tower.append_digest(second.clone()).unwrap();
+    tower.append(b"b".to_vec());
+    assert!(tower.validate().is_ok());
+
+    // Digest entries enter their groups as stored bytes
+    let left = MockDigest::digest_items(&[&b"a"[..], &first]);
+    let right = MockDigest::digest_items(&[&second[..], b"b"]);
+    let root = MockDigest::digest_items(&[&left, &right]);
+    assert_eq!(tower.root_digest(), Some(root));
+
+    for index in 0..4 {
+        let proof = tower.generate_proof(index).unwrap();
+        assert!(proof.verify(), "index {}", index);
+        assert!(tower.verify(&proof));
+    }
+    let proof = tower.generate_proof(1).unwrap();
+    assert_eq!(proof.item, first);
+    assert_eq!(
+        proof.path.elements[0],
+        PathElement::RawSiblings {
+            position: 1,
+            siblings: vec![b"a".to_vec()]
+        }
+    );
+
+    // A lone digest leaf is the root itself
+    let mut single: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    single.append_digest(first.clone()).unwrap();
+    assert_eq!(single.root_digest(), Some(first.clone()));
+    let proof = single.generate_proof(0).unwrap();
+    assert!(proof.path.elements.is_empty());
+    assert!(single.verify(&proof));
+
+    // An ordinary lone item does not accept its own bytes as the root
+    let mut plain: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    plain.append(first.clone());
+    let mut forged = plain.generate_proof(0).unwrap();
+    forged.item = forged.root.clone();
+    assert!(!plain.verify(&forged));
+}
+
+#[test]
+fn test_item_edits_keep_digest_leaves_as_digests() {
+    let first = MockDigest::digest_item(&b"x");
+    let second = MockDigest::digest_item(&b"y");
+    let mut tower: LazyTower<Vec<u8>, MockDigest> =
+        LazyTower::from_leaf_digests(2, vec![first.clone()]).unwrap();
+
+    // Touching the lone leaf without changing it keeps it as the root
+    drop(tower.get_mut(0).unwrap());
+    assert_eq!(tower.root_digest(), Some(first.clone()));
+
+    // An edited digest leaf is still its own root
+    *tower.get_mut(0).unwrap() = second.clone();
+    assert_eq!(tower.root_digest(), Some(second.clone()));
+    let proof = tower.generate_proof(0).unwrap();
+    assert!(tower.verify(&proof));
+
+    // Bigger towers fold the edited leaf as stored bytes
+    let mut tower: LazyTower<Vec<u8>, MockDigest> =
+        LazyTower::from_leaf_digests(2, vec![first.clone(), first.clone()]).unwrap();
+    *tower.get_mut(1).unwrap() = second.clone();
+    let expected: LazyTower<Vec<u8>, MockDigest> =
+        LazyTower::from_leaf_digests(2, vec![first, second]).unwrap();
+    assert_eq!(tower.root_digest(), expected.root_digest());
+}
+
+#[test]
+fn test_appended_digest_in_nonce_tower_verifies() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> =
+        LazyTower::new_with_nonce(2, b"n".to_vec()).unwrap();
+    let leaf = MockDigest::digest_item(&b"x");
+    tower.append(b"a".to_vec());
+    tower.append_digest(leaf.clone()).unwrap();
+
+    let proof = tower.generate_proof(1).unwrap();
+    assert_eq!(proof.item, leaf);
+    assert!(proof.verify());
+    assert!(tower.verify(&proof));
+
+    // The digest is bound to the nonce like an ordinary item
+    let mut plain: LazyTower<Vec<u8>, MockDigest> =
+        LazyTower::new_with_nonce(2, b"n".to_vec()).unwrap();
+    plain.append(b"a".to_vec());
+    plain.append(leaf);
+    assert_eq!(tower.root_digest(), plain.root_digest());
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn test_proof_generation_from_sha256_leaf_digests() {
+    use lazytower_rs::digest::sha256::Sha256Digest;
+
+    let leaves: Vec<[u8; 32]> = ["A", "B", "C", "D"]
+        .iter()
+        .map(Sha256Digest::digest_item)
+        .collect();
+    let tower: LazyTower<[u8; 32], Sha256Digest> =
+        LazyTower::from_leaf_digests(2, leaves.clone()).unwrap();
+
+    for (i, leaf) in leaves.iter().enumerate() {
+        let proof = tower.generate_proof(i).unwrap();
+        assert_eq!(&proof.item, leaf);
+        assert!(proof.verify(), "Proof verification failed for leaf {}", i);
+    }
+}
+
+#[test]
+fn test_proof_verification_per_level_widths() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new_with_widths(vec![2, 3]).unwrap();
+
+    for i in 0..6 {
+        tower.append(vec![i]);
+    }
+
+    for i in 0..6 {
+        let proof = tower.generate_proof(i).unwrap();
+        assert!(proof.verify(), "Proof verification failed for item {}", i);
+    }
+}
+
+#[test]
+fn test_standalone_proof_path_verifies() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    for i in 0..4 {
+        tower.append(vec![i]);
+    }
+
+    let root = tower.root_digest().unwrap();
+    for i in 0..4 {
+        let path = tower.proof_path_for(i).unwrap();
+        let item = tower.get(i).unwrap();
+        assert!(path.verify(item, &root), "Standalone path failed for {}", i);
+        assert_eq!(path, tower.generate_proof(i).unwrap().path);
+    }
+
+    assert!(tower.proof_path_for(4).is_err());
+    assert!(tower.get(4).is_none());
+}
+
+#[test]
+fn test_share_suffix_for_sibling_and_unrelated_items() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    for i in 0..8 {
+        tower.append(vec![i]);
+    }
+
+    // Items 0 and 1 share a level 0 group, so everything above it matches
+    let path_0 = tower.proof_path_for(0).unwrap();
+    let path_1 = tower.proof_path_for(1).unwrap();
+    assert_eq!(path_0.share_suffix(&path_1), path_0.elements.len() - 1);
+    assert!(path_0.share_suffix(&path_1) > 0);
+
+    // Items 0 and 7 only meet at the root group, where their positions differ
+    let path_7 = tower.proof_path_for(7).unwrap();
+    assert_eq!(path_0.share_suffix(&path_7), 0);
+
+    assert_eq!(path_0.share_suffix(&path_0), path_0.elements.len());
+}
+
+// ===== Proof Cache Tests =====
+
+thread_local! {
+    static DIGEST_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Digest that counts calls on the current thread
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CountingDigest;
+
+impl CountingDigest {
+    fn calls() -> usize {
+        DIGEST_CALLS.with(|calls| calls.get())
+    }
+}
+
+impl Digest for CountingDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        DIGEST_CALLS.with(|calls| calls.set(calls.get() + 1));
+        MockDigest::digest_item(item)
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        DIGEST_CALLS.with(|calls| calls.set(calls.get() + 1));
+        MockDigest::digest_items(items)
+    }
+}
+
+#[test]
+fn test_cached_proof_performs_no_digest_work() {
+    let mut tower: LazyTower<Vec<u8>, CountingDigest> = LazyTower::new(4).unwrap();
+    tower.enable_proof_cache();
+    for i in 0..3 {
+        tower.append(vec![i]);
+    }
+
+    let before = CountingDigest::calls();
+    let first = tower.generate_proof(1).unwrap();
+    assert!(CountingDigest::calls() > before);
+
+    let before = CountingDigest::calls();
+    let second = tower.generate_proof(1).unwrap();
+    assert_eq!(CountingDigest::calls(), before);
+    assert_eq!(second.path, first.path);
+    assert_eq!(second.root, first.root);
+
+    // Appending invalidates the cache
+    tower.append(vec![3]);
+    let refreshed = tower.generate_proof(1).unwrap();
+    assert_ne!(refreshed.root, first.root);
+    assert!(refreshed.verify());
+}
+
+#[test]
+fn test_multi_node_top_level_is_combined_once() {
+    let mut tower: LazyTower<Vec<u8>, CountingDigest> = LazyTower::new(4).unwrap();
+    for i in 0..14 {
+        tower.append(vec![i]);
+    }
+    assert_eq!(tower.level(1).map(|level| level.len()), Some(3));
+    assert_eq!(tower.level(0).map(|level| level.len()), Some(2));
+
+    let before = CountingDigest::calls();
+    let root = tower.root_digest().unwrap();
+    assert_eq!(CountingDigest::calls(), before + 1);
+
+    for _ in 0..3 {
+        assert_eq!(tower.root_digest().unwrap(), root);
+    }
+    assert_eq!(CountingDigest::calls(), before + 1);
+
+    // The next append leaves the top level as it was but still drops the cache
+    tower.append(vec![14]);
+    let before = CountingDigest::calls();
+    assert_eq!(tower.root_digest().unwrap(), root);
+    assert_eq!(CountingDigest::calls(), before + 1);
+
+    // Once the top level overflows the root is a single digest again
+    tower.append(vec![15]);
+    assert_eq!(tower.level(2).map(|level| level.len()), Some(1));
+    let before = CountingDigest::calls();
+    let grown = tower.root_digest().unwrap();
+    assert_eq!(CountingDigest::calls(), before);
+    assert_ne!(grown, root);
+}
+
+#[test]
+fn test_proof_path_encode_round_trip() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(3).unwrap();
+    for i in 0..20 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+
+    for index in [0, 7, 13] {
+        let proof = tower.generate_proof(index).unwrap();
+        let bytes = proof.path.encode();
+        let decoded = ProofPath::<MockDigest>::decode(&bytes).unwrap();
+
+        assert_eq!(decoded, proof.path);
+        assert!(decoded.verify(&proof.item, &proof.root));
+    }
+}
+
+#[test]
+fn test_proof_path_decode_truncated_buffer() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    for i in 0..8 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+
+    let bytes = tower.generate_proof(2).unwrap().path.encode();
+    let result = ProofPath::<MockDigest>::decode(&bytes[..bytes.len() - 1]);
+
+    assert!(matches!(result, Err(LazyTowerError::Decode { .. })));
+}
+
+#[test]
+fn test_proofs_bind_item_metadata() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(3).unwrap();
+    for i in 0..10u64 {
+        let item = TestItem(format!("event{}", i));
+        if i == 4 {
+            tower.append(item);
+        } else {
+            tower.append_with_meta(item, 1_700_000_000 + i);
+        }
+    }
+    tower.flush();
+
+    assert_eq!(tower.meta(2), Some(1_700_000_002));
+    assert_eq!(tower.meta(4), None);
+
+    for index in 0..10 {
+        let proof = tower.generate_proof(index).unwrap();
+        assert_eq!(proof.meta, tower.meta(index));
+        assert!(proof.verify(), "proof for item {} should verify", index);
+    }
+
+    // Claiming a different timestamp, or none at all, fails
+    let mut proof = tower.generate_proof(2).unwrap();
+    proof.meta = Some(1_700_000_003);
+    assert!(!proof.verify());
+    proof.meta = None;
+    assert!(!proof.verify());
+}
+
+#[test]
+fn test_single_item_with_metadata_verifies() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(4).unwrap();
+    tower.append_with_meta(TestItem("only".to_string()), 7);
+
+    let mut proof = tower.generate_proof(0).unwrap();
+    assert!(proof.verify());
+    proof.meta = Some(8);
+    assert!(!proof.verify());
+}
+
+#[test]
+fn test_verify_against_any_finds_matching_root() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    let mut roots = Vec::new();
+    for i in 0..4 {
+        tower.append(TestItem(format!("item{}", i)));
+        roots.push(tower.root_digest().unwrap());
+    }
+
+    // Roots after 2, 3 and 4 appends; only the last commits to item 2
+    let candidates = roots[1..].to_vec();
+    let proof = tower.generate_proof(2).unwrap();
+    assert_eq!(proof.verify_against_any(&candidates), Some(2));
+}
+
+#[test]
+fn test_verify_against_any_without_match() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    for i in 0..4 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+
+    let proof = tower.generate_proof(1).unwrap();
+    let candidates = vec![
+        b"stale".to_vec(),
+        MockDigest::digest_item(&b"other"),
+        Vec::new(),
+    ];
+    assert_eq!(proof.verify_against_any(&candidates), None);
+    assert_eq!(proof.verify_against_any(&[]), None);
+}
+
+#[test]
+fn test_hashed_leaves_root_convention() {
+    let options = TowerOptions {
+        hash_leaves: true,
+        ..TowerOptions::default()
+    };
+    let mut tower = LazyTower::<TestItem, MockDigest>::with_options(3, options).unwrap();
+    let items: Vec<TestItem> = ["A", "B", "C", "D"]
+        .iter()
+        .map(|s| TestItem(s.to_string()))
+        .collect();
+    let leaf = |item: &TestItem| MockDigest::digest_item(item);
+
+    tower.append(items[0].clone());
+    assert_eq!(tower.root_digest(), Some(leaf(&items[0])));
+
+    tower.append(items[1].clone());
+    assert_eq!(
+        tower.root_digest(),
+        Some(MockDigest::digest_items(&[
+            leaf(&items[0]),
+            leaf(&items[1])
+        ]))
+    );
+
+    tower.append(items[2].clone());
+    let group = MockDigest::digest_items(&[leaf(&items[0]), leaf(&items[1]), leaf(&items[2])]);
+    assert_eq!(tower.root_digest(), Some(group));
+
+    tower.append(items[3].clone());
+    assert!(tower.verify_root_against_items(&items));
+}
+
+#[test]
+fn test_hashed_leaves_proofs_verify() {
+    let options = TowerOptions {
+        hash_leaves: true,
+        ..TowerOptions::default()
+    };
+
+    for count in 1..40 {
+        let mut tower =
+            LazyTower::<TestItem, MockDigest>::with_options(3, options.clone()).unwrap();
+        let items: Vec<TestItem> = (0..count).map(|i| TestItem(format!("item{}", i))).collect();
+        for item in &items {
+            tower.append(item.clone());
+        }
+        tower.flush();
+
+        let skeleton = tower.skeleton();
+        for index in 0..count {
+            let proof = tower.generate_proof(index).unwrap();
+            assert!(proof.verify(), "{} items, index {}", count, index);
+
+            // Bottom-level siblings are leaf digests, never raw items
+            assert!(!matches!(
+                proof.path.elements.first(),
+                Some(PathElement::RawSiblings { .. })
+            ));
+
+            let from_skeleton = skeleton.prove(index, &items).unwrap();
+            assert_eq!(from_skeleton.path, proof.path);
+        }
+    }
+}
+
+#[test]
+fn test_width_two_hashed_leaves_combine_leaf_digests() {
+    let options = TowerOptions {
+        hash_leaves: true,
+        ..TowerOptions::default()
+    };
+    let mut tower = LazyTower::<TestItem, MockDigest>::with_options(2, options).unwrap();
+    let items: Vec<TestItem> = (0..8).map(|i| TestItem(format!("item{}", i))).collect();
+    for item in &items {
+        tower.append(item.clone());
+    }
+    let combine = |a: &TestItem, b: &TestItem| {
+        MockDigest::digest_items(&[MockDigest::digest_item(a), MockDigest::digest_item(b)])
+    };
+    let pairs: Vec<Vec<u8>> = (0..4)
+        .map(|i| combine(&items[2 * i], &items[2 * i + 1]))
+        .collect();
+    let root = MockDigest::digest_items(&[
+        MockDigest::digest_items(&pairs[0..2]),
+        MockDigest::digest_items(&pairs[2..4]),
+    ]);
+    assert_eq!(tower.root_digest(), Some(root));
+
+    for index in 0..8 {
+        let proof = tower.generate_proof(index).unwrap();
+        assert!(proof.verify(), "index {}", index);
+
+        // Level 0 pairs the leaf digests of the item and its neighbour
+        let pair = index ^ 1;
+        let (position, sibling) = match &proof.path.elements[0] {
+            PathElement::Siblings { position, siblings } => (*position, siblings[0].clone()),
+            other => panic!("expected leaf digest siblings, got {:?}", other),
+        };
+        assert_eq!(position, index & 1);
+        assert_eq!(sibling, MockDigest::digest_item(&items[pair]));
+
+        // The same path read as raw level-0 siblings hashes the raw bytes
+        // with digest_items and misses the root
+        let mut raw = proof.path.clone();
+        raw.elements[0] = PathElement::RawSiblings {
+            position,
+            siblings: vec![items[pair].0.clone().into_bytes()],
+        };
+        assert!(!raw.verify(&proof.item, &proof.root));
+    }
+}
+
+#[cfg(feature = "test-utils")]
+#[test]
+fn test_duplicate_digests_keep_their_own_groups() {
+    use lazytower_rs::digest::mock::IdentityDigest;
+
+    // Repeated items give every group at a level the same digest bytes
+    let mut tower = LazyTower::<TestItem, IdentityDigest>::new(2).unwrap();
+    let items: Vec<TestItem> = ["a", "a", "a", "a", "b", "b", "a", "a"]
+        .iter()
+        .map(|s| TestItem(s.to_string()))
+        .collect();
+    for item in &items {
+        tower.append(item.clone());
+    }
+    assert!(tower.validate().is_ok());
+
+    let skeleton = tower.skeleton();
+    for (index, item) in items.iter().enumerate() {
+        let proof = tower.generate_proof(index).unwrap();
+        assert!(proof.verify(), "index {}", index);
+        assert_eq!(&proof.item, item);
+        assert_eq!(proof.path, skeleton.prove(index, &items).unwrap().path);
+
+        // The level 1 sibling is the neighbouring pair, not an equal-valued
+        // group elsewhere in the tower
+        let pair = index ^ 2;
+        let expected = format!("{}{}", items[pair & !1].0, items[pair | 1].0).into_bytes();
+        match &proof.path.elements[1] {
+            PathElement::Siblings { siblings, .. } => assert_eq!(siblings[0], expected),
+            PathElement::RawSiblings { siblings, .. } => assert_eq!(siblings[0], expected),
+        }
+    }
+}
+
+#[test]
+fn test_proof_construction_is_deterministic() {
+    let build = || {
+        let mut tower = LazyTower::<TestItem, MockDigest>::new(3).unwrap();
+        for i in 0..7 {
+            tower.append(TestItem(format!("item{}", i)));
+        }
+        tower
+    };
+
+    let reference = build();
+    let expected = reference.generate_proof(4).unwrap().path.encode();
+
+    for _ in 0..100 {
+        let tower = build();
+        assert_eq!(tower.generate_proof(4).unwrap().path.encode(), expected);
+
+        #[cfg(feature = "test-utils")]
+        assert_eq!(
+            tower.proof_traversal(4).unwrap(),
+            reference.proof_traversal(4).unwrap()
+        );
+    }
+
+    #[cfg(feature = "test-utils")]
+    assert_eq!(reference.proof_traversal(4).unwrap(), vec![(0, 1), (1, 1)]);
+}
+
+#[test]
+fn test_is_valid_shape_accepts_generated_paths() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    for i in 0..6 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+
+    for index in 0..4 {
+        let path = tower.generate_proof(index).unwrap().path;
+        assert!(path.is_valid_shape(&[2]));
+    }
+    assert!(ProofPath::<MockDigest>::new().is_valid_shape(&[2]));
+}
+
+#[test]
+fn test_is_valid_shape_follows_per_level_widths() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new_with_widths(vec![2, 3]).unwrap();
+    for i in 0..12 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+
+    for index in 0..12 {
+        let path = tower.generate_proof(index).unwrap().path;
+        assert_eq!(path.elements.len(), 3);
+        assert!(path.is_valid_shape(tower.widths()));
+        assert!(path.is_valid_shape(&[2, 3, 3]));
+
+        // No single width fits both the pairs and the triples
+        assert!(!path.is_valid_shape(&[2]));
+        assert!(!path.is_valid_shape(&[3]));
+    }
+    assert!(!ProofPath::<MockDigest>::new().is_valid_shape(&[]));
+}
+
+#[test]
+fn test_is_valid_shape_rejects_oversized_level() {
+    let mut path = ProofPath::<MockDigest>::new();
+    path.add_raw_siblings(0, vec![b"B".to_vec()]);
+    path.add_siblings(1, vec![vec![1]; 5]);
+
+    assert!(!path.is_valid_shape(&[2]));
+    assert!(path.is_valid_flushed_shape(&[6]));
+
+    // A position past the end of the group is impossible
+    let mut path = ProofPath::<MockDigest>::new();
+    path.add_raw_siblings(3, vec![b"B".to_vec()]);
+    assert!(!path.is_valid_shape(&[4]));
+}
+
+#[test]
+fn test_is_valid_shape_rejects_short_lower_level() {
+    // A lower level of a width-4 tower always holds three siblings
+    let mut path = ProofPath::<MockDigest>::new();
+    path.add_raw_siblings(0, vec![b"B".to_vec()]);
+    path.add_siblings(1, vec![vec![1]]);
+
+    assert!(!path.is_valid_shape(&[4]));
+    assert!(path.is_valid_flushed_shape(&[4]));
+
+    let mut path = ProofPath::<MockDigest>::new();
+    path.add_raw_siblings(0, vec![b"B".to_vec(), b"C".to_vec(), b"D".to_vec()]);
+    path.add_siblings(1, vec![vec![1]]);
+    assert!(path.is_valid_shape(&[4]));
+}
+
+#[test]
+fn test_is_valid_flushed_shape_accepts_flushed_paths() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(4).unwrap();
+    for i in 0..6 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+    tower.flush();
+
+    let path = tower.generate_proof(4).unwrap().path;
+    assert!(!path.is_valid_shape(&[4]));
+    assert!(path.is_valid_flushed_shape(&[4]));
+
+    for index in 0..6 {
+        let path = tower.generate_proof(index).unwrap().path;
+        assert!(path.is_valid_flushed_shape(&[4]));
+    }
+}
+
+#[test]
+fn test_expected_path_length_matches_generated_proofs() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    for i in 0..7 {
+        tower.append(TestItem(format!("item{}", i)));
+        for index in 0..=i {
+            assert_eq!(
+                tower.generate_proof(index).unwrap().path.elements.len(),
+                tower.expected_path_length(index),
+                "{} items, index {}",
+                i + 1,
+                index
+            );
+        }
+    }
+
+    // Items 0..4 climb two groups, 4..6 one and the lone item 6 none
+    let lengths: Vec<usize> = (0..7).map(|i| tower.expected_path_length(i)).collect();
+    assert_eq!(lengths, vec![2, 2, 2, 2, 1, 1, 0]);
+    assert_eq!(tower.expected_path_length(7), 0);
+}
+
+#[test]
+fn test_nonce_separates_towers_over_identical_data() {
+    let build = |nonce: &[u8]| {
+        let mut tower =
+            LazyTower::<TestItem, MockDigest>::new_with_nonce(3, nonce.to_vec()).unwrap();
+        for i in 0..10 {
+            tower.append(TestItem(format!("item{}", i)));
+        }
+        tower.append_with_meta(TestItem("stamped".to_string()), 42);
+        tower.flush();
+        tower
+    };
+    let first = build(b"first");
+    let second = build(b"second");
+    assert_eq!(first.nonce(), Some(&b"first"[..]));
+    assert_ne!(first.root_digest(), second.root_digest());
+
+    let mut plain = LazyTower::<TestItem, MockDigest>::new(3).unwrap();
+    for i in 0..10 {
+        plain.append(TestItem(format!("item{}", i)));
+    }
+    plain.append_with_meta(TestItem("stamped".to_string()), 42);
+    plain.flush();
+    assert_ne!(plain.root_digest(), first.root_digest());
+
+    for index in 0..11 {
+        let proof = first.generate_proof(index).unwrap();
+        assert_eq!(proof.nonce.as_deref(), Some(&b"first"[..]));
+        assert!(proof.verify(), "index {}", index);
+        assert!(first.verify(&proof));
+        assert!(!second.verify(&proof));
+
+        // Claiming the other tower's nonce does not help either
+        let mut forged = proof.clone();
+        forged.nonce = Some(b"second".to_vec());
+        assert!(!forged.verify());
+        assert!(!second.verify(&forged));
+    }
+
+    // A lone item is its own nonced leaf
+    let mut single = LazyTower::<TestItem, MockDigest>::new_with_nonce(2, b"n".to_vec()).unwrap();
+    single.append(TestItem("only".to_string()));
+    assert!(single.generate_proof(0).unwrap().verify());
+}
+
+#[test]
+fn test_from_levels_builds_verifiable_path() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    for name in ["A", "B", "C", "D"] {
+        tower.append(TestItem(name.to_string()));
+    }
+    let root = tower.root_digest().unwrap();
+
+    // C pairs with raw D, then its group sits right of digest_items[A,B]
+    let path = ProofPath::<MockDigest>::from_levels(vec![
+        (PathKind::RawSiblings, 0, vec![b"D".to_vec()]),
+        (PathKind::Siblings, 1, vec![b"digest_items[A,B]".to_vec()]),
+    ])
+    .unwrap();
+
+    assert_eq!(path, tower.generate_proof(2).unwrap().path);
+    assert!(path.verify(&TestItem("C".to_string()), &root));
+    assert!(!path.verify(&TestItem("D".to_string()), &root));
+    assert!(ProofPath::<MockDigest>::from_levels(Vec::new())
+        .unwrap()
+        .elements
+        .is_empty());
+}
+
+#[test]
+fn test_try_new_validates_path_shape() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    for i in 0..4 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+    let generated = tower.generate_proof(2).unwrap();
+
+    let proof = MembershipProof::try_new(
+        generated.item.clone(),
+        generated.path.clone(),
+        generated.root.clone(),
+    )
+    .unwrap();
+    assert!(proof.verify());
+    assert_eq!(proof.meta, None);
+
+    // Position 3 cannot sit among a single sibling
+    let mut path = ProofPath::<MockDigest>::new();
+    path.add_raw_siblings(3, vec![b"B".to_vec()]);
+    let err = MembershipProof::try_new(TestItem("A".to_string()), path, generated.root.clone())
+        .unwrap_err();
+    assert!(matches!(err, LazyTowerError::MalformedProof { .. }));
+
+    // A top level without siblings cannot come from a tower
+    let mut path = ProofPath::<MockDigest>::new();
+    path.add_siblings(0, Vec::new());
+    assert!(matches!(
+        MembershipProof::try_new(TestItem("A".to_string()), path, generated.root),
+        Err(LazyTowerError::MalformedProof { .. })
+    ));
+}
+
+#[test]
+fn test_verify_rejects_position_past_siblings() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    for i in 0..4 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+    // Item 1 sits last in its group, so any larger position would rebuild
+    // the same group if it were not rejected
+    let mut proof = tower.generate_proof(1).unwrap();
+    assert!(proof.verify());
+
+    for bogus in [2, 3, usize::MAX] {
+        match &mut proof.path.elements[0] {
+            PathElement::Siblings { position, .. } | PathElement::RawSiblings { position, .. } => {
+                *position = bogus
+            }
+        }
+        assert!(!proof.verify());
+        assert!(!proof.path.verify(&proof.item, &proof.root));
+        let mut scratch = Vec::new();
+        assert!(!proof
+            .path
+            .verify_into(&proof.item, &proof.root, &mut scratch));
+        assert!(!tower.verify(&proof));
+    }
+}
+
+#[test]
+fn test_tower_verify_rejects_stale_proof() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    for i in 0..4 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+
+    let proof = tower.generate_proof(1).unwrap();
+    assert!(tower.verify(&proof));
+
+    for i in 4..8 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+
+    // Still valid for the root it carries, but not for the current tower
+    assert!(proof.verify());
+    assert!(!tower.verify(&proof));
+    assert!(tower.verify(&tower.generate_proof(1).unwrap()));
+
+    let empty = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    assert!(!empty.verify(&proof));
+}
+
+#[test]
+fn test_verify_into_reuses_scratch_buffer() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(3).unwrap();
+    for i in 0..40 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+    let root = tower.root_digest().unwrap();
+    let wrong_item = TestItem("forged".to_string());
+
+    let mut scratch = Vec::new();
+    for index in 0..40 {
+        let proof = tower.generate_proof(index).unwrap();
+        assert_eq!(
+            proof.path.verify_into(&proof.item, &root, &mut scratch),
+            proof.path.verify(&proof.item, &root),
+            "index {}",
+            index
+        );
+        assert!(!proof.path.verify_into(&wrong_item, &root, &mut scratch));
+    }
+
+    // Multi-level proofs verify with the same buffer
+    let proof = tower.generate_proof(0).unwrap();
+    assert!(proof.path.elements.len() > 1);
+    assert!(proof.path.verify_into(&proof.item, &root, &mut scratch));
+    assert!(ProofPath::<MockDigest>::new().verify_into(
+        &b"x",
+        &MockDigest::digest_item(&b"x"),
+        &mut scratch
+    ));
+}
+
+/// Incremental hasher recording its input, wrapped as `digest(...)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockIncremental;
+
+impl IncrementalDigest for MockIncremental {
+    type Hasher = Vec<u8>;
+    type Output = Vec<u8>;
+
+    fn new_hasher() -> Self::Hasher {
+        b"digest(".to_vec()
+    }
+
+    fn update(hasher: &mut Self::Hasher, bytes: &[u8]) {
+        hasher.extend_from_slice(bytes);
+    }
+
+    fn finalize(mut hasher: Self::Hasher) -> Self::Output {
+        hasher.push(b')');
+        hasher
+    }
+}
+
+#[test]
+fn test_verify_streamed_matches_verify() {
+    type Streamed = Incremental<MockIncremental>;
+
+    let wrong_item = TestItem("forged".to_string());
+    for (width, hash_leaves) in [(3, false), (3, true), (20, false)] {
+        let options = TowerOptions {
+            hash_leaves,
+            ..TowerOptions::default()
+        };
+        let mut tower = LazyTower::<TestItem, Streamed>::with_options(width, options).unwrap();
+        for i in 0..50 {
+            tower.append(TestItem(format!("item{}", i)));
+        }
+        let root = tower.root_digest().unwrap();
+
+        for index in 0..50 {
+            let proof = tower.generate_proof(index).unwrap();
+            assert_eq!(
+                proof.path.verify_streamed(&proof.item, &root),
+                proof.path.verify(&proof.item, &root),
+                "width {} index {}",
+                width,
+                index
+            );
+            assert!(!proof.path.verify_streamed(&wrong_item, &root));
+        }
+        let proof = tower.generate_proof(0).unwrap();
+        assert!(proof.path.elements.len() > 1);
+        assert!(proof.path.verify_streamed(&proof.item, &root));
+    }
+
+    assert!(ProofPath::<Streamed>::new().verify_streamed(&b"x", &Streamed::digest_item(&b"x")));
+}
+
+#[test]
+fn test_build_with_all_proofs_lazy_matches_generate_proof() {
+    let items: Vec<TestItem> = (0..1000).map(|i| TestItem(format!("item{}", i))).collect();
+    // 1000 items fill width-10 levels exactly, leaving a single top digest
+    let mut proofs =
+        LazyTower::<TestItem, MockDigest>::build_with_all_proofs_lazy(10, items).unwrap();
+    assert_eq!(proofs.size_hint(), (0, Some(1000)));
+    assert_eq!(proofs.tower().len(), 1000);
+
+    // Pseudo-random sample of indices, checked as the iterator reaches them
+    let mut sample = Vec::new();
+    let mut state = 17usize;
+    for _ in 0..50 {
+        state = (state * 1103515245 + 12345) % 1000;
+        sample.push(state);
+    }
+    sample.sort_unstable();
+    sample.dedup();
+
+    let mut yielded = 0;
+    let mut checked = 0;
+    while let Some((index, proof)) = proofs.next() {
+        assert_eq!(index, yielded);
+        yielded += 1;
+        if sample.binary_search(&index).is_ok() {
+            assert!(proof.verify(), "index {}", index);
+            let expected = proofs.tower().generate_proof(index).unwrap();
+            assert_eq!(proof.item, expected.item);
+            assert_eq!(proof.path, expected.path);
+            assert_eq!(proof.root, expected.root);
+            checked += 1;
+        }
+    }
+    assert_eq!(yielded, 1000);
+    assert_eq!(checked, sample.len());
+    assert!(proofs.next().is_none());
+
+    let tower = proofs.into_tower();
+    assert!(tower.validate().is_ok());
+}
+
+#[test]
+fn test_position_bits_recover_width_two_index() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    for i in 0..8 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+
+    let proof = tower.generate_proof(5).unwrap();
+    assert_eq!(proof.path.elements.len(), 3);
+    assert_eq!(proof.path.position_bits(), 5);
+
+    for index in 0..8 {
+        let proof = tower.generate_proof(index).unwrap();
+        assert_eq!(proof.path.position_bits(), index as u64);
+    }
+    assert_eq!(ProofPath::<MockDigest>::new().position_bits(), 0);
+}
+
+#[test]
+fn test_proofs_under_partial_top_level_match_root() {
+    // Width 4 with 8 and 12 items leaves 2 and 3 digests on the top level
+    for (count, top_nodes) in [(8, 2), (12, 3)] {
+        let mut tower = LazyTower::<TestItem, MockDigest>::new(4).unwrap();
+        for i in 0..count {
+            tower.append(TestItem(format!("item{}", i)));
+        }
+        assert_eq!(tower.height(), 2);
+        assert!(tower.level(0).unwrap().is_empty());
+        assert_eq!(tower.level(1).unwrap().len(), top_nodes);
+
+        let root = tower.root_digest().unwrap();
+        for index in 0..count {
+            let proof = tower.generate_proof(index).unwrap();
+            assert_eq!(proof.root, root);
+            assert!(proof.verify(), "proof for item {} failed", index);
+            assert!(proof.path.verify(&proof.item, &root));
+        }
+    }
+}
+
+#[test]
+fn test_verify_to_intermediate_level() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    for i in 0..8 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+    let proof = tower.generate_proof(5).unwrap();
+    assert_eq!(proof.path.elements.len(), 3);
+
+    // Item 5 is digested with item 4 into its level-1 node
+    let level1 = MockDigest::digest_items(&[b"item4".as_ref(), b"item5".as_ref()]);
+    assert!(proof.path.verify_to(&proof.item, &level1, 1));
+    assert!(!proof.path.verify_to(&proof.item, &level1, 2));
+    assert!(!proof
+        .path
+        .verify_to(&TestItem("item4".to_string()), &level1, 1));
+
+    let truncated = proof.path.truncate_to_level(1);
+    assert_eq!(truncated.elements.len(), 1);
+    assert!(truncated.verify(&proof.item, &level1));
+
+    // Past the end the whole path is kept and reaches the root
+    assert_eq!(proof.path.truncate_to_level(10).elements.len(), 3);
+    assert!(proof.path.verify_to(&proof.item, &proof.root, 10));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_parallel_batch_matches_serial_batch() {
+    let items: Vec<TestItem> = (0..5000).map(|i| TestItem(format!("item{}", i))).collect();
+    let hashed = TowerOptions {
+        hash_leaves: true,
+        ..TowerOptions::default()
+    };
+    let towers: Vec<LazyTower<TestItem, MockDigest>> = vec![
+        LazyTower::new(4).unwrap(),
+        LazyTower::with_options(4, hashed).unwrap(),
+        LazyTower::new_with_nonce(4, b"salt".to_vec()).unwrap(),
+    ];
+
+    for tower in towers {
+        let mut serial = tower.clone();
+        let mut parallel = tower;
+        let serial_indices = serial.append_batch_atomic(items.clone()).unwrap();
+        let parallel_indices = parallel.append_batch_par(items.clone()).unwrap();
+
+        assert_eq!(serial_indices, parallel_indices);
+        assert_eq!(parallel.len(), 5000);
+        assert_eq!(serial.root_digest(), parallel.root_digest());
+        // Items past 4096 sit below the top level, which the root does not cover
+        for index in [0, 1, 1234, 4095] {
+            let expected = serial.generate_proof(index).unwrap();
+            let proof = parallel.generate_proof(index).unwrap();
+            assert_eq!(proof.path, expected.path);
+            assert_eq!(proof.root, expected.root);
+            assert!(parallel.verify(&proof));
+        }
+    }
+}
+
+#[test]
+fn test_generate_proof_latest_matches_generate_proof() {
+    let hashed = TowerOptions {
+        hash_leaves: true,
+        ..TowerOptions::default()
+    };
+    let towers: Vec<LazyTower<TestItem, MockDigest>> = vec![
+        LazyTower::new(3).unwrap(),
+        LazyTower::with_options(3, hashed).unwrap(),
+    ];
+
+    for mut tower in towers {
+        assert!(matches!(
+            tower.generate_proof_latest(),
+            Err(LazyTowerError::InvalidIndex { .. })
+        ));
+
+        for i in 0..40 {
+            tower.append(TestItem(format!("item{}", i)));
+            let latest = tower.generate_proof_latest().unwrap();
+            let expected = tower.generate_proof(tower.len() - 1).unwrap();
+            assert_eq!(latest.item, expected.item);
+            assert_eq!(latest.path, expected.path, "path after {} items", i + 1);
+            assert_eq!(latest.root, expected.root);
+            assert_eq!(latest.verify(), expected.verify());
+        }
+    }
+}
+
+#[test]
+fn test_proved_index_and_index_checked_verification() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    for i in 0..8 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+    for index in 0..8 {
+        let proof = tower.generate_proof(index).unwrap();
+        assert_eq!(proof.proved_index(), Some(index));
+        assert!(tower.verify_proof_for_index(&proof, index));
+    }
+
+    // A valid proof for item 3 is not a proof for item 5
+    let proof = tower.generate_proof(3).unwrap();
+    assert!(tower.verify(&proof));
+    assert!(!tower.verify_proof_for_index(&proof, 5));
+    assert!(!tower.verify_proof_for_index(&proof, 8));
+
+    // Wider towers compare against the generated path instead
+    let mut wide = LazyTower::<TestItem, MockDigest>::new(3).unwrap();
+    for i in 0..9 {
+        wide.append(TestItem(format!("item{}", i)));
+    }
+    let proof = wide.generate_proof(3).unwrap();
+    assert_eq!(proof.proved_index(), None);
+    assert!(wide.verify_proof_for_index(&proof, 3));
+    assert!(!wide.verify_proof_for_index(&proof, 5));
+}
+
+#[test]
+fn test_compact_renumbers_kept_items() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    for i in 0..12 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+    let old_root = tower.root_digest();
+
+    let dropped = [1, 4, 5, 9];
+    let remap = tower.compact(|index, _| !dropped.contains(&index)).unwrap();
+
+    assert_eq!(remap.len(), 8);
+    assert_eq!(remap[&0], 0);
+    assert_eq!(remap[&2], 1);
+    assert_eq!(remap[&6], 3);
+    assert_eq!(remap[&11], 7);
+    assert!(dropped.iter().all(|index| !remap.contains_key(index)));
+
+    assert_eq!(tower.len(), 8);
+    assert_ne!(tower.root_digest(), old_root);
+    for (&old, &new) in &remap {
+        assert_eq!(tower.get(new), Some(&TestItem(format!("item{}", old))));
+        let proof = tower.generate_proof(new).unwrap();
+        assert!(tower.verify(&proof));
+        assert!(tower.verify_proof_for_index(&proof, new));
+    }
+
+    // The root matches a tower built from the kept items directly
+    let mut fresh = LazyTower::<TestItem, MockDigest>::new(2).unwrap();
+    for i in (0..12).filter(|i| !dropped.contains(i)) {
+        fresh.append(TestItem(format!("item{}", i)));
+    }
+    assert_eq!(tower.root_digest(), fresh.root_digest());
+}
+
+#[test]
+fn test_compact_keeps_digest_leaves_as_digests() {
+    let leaves: Vec<Vec<u8>> = (0..5u8).map(|i| MockDigest::digest_item(&[i])).collect();
+
+    // Keeping every leaf leaves the root as it was
+    let mut tower: LazyTower<Vec<u8>, MockDigest> =
+        LazyTower::from_leaf_digests(2, leaves.clone()).unwrap();
+    let root = tower.root_digest();
+    tower.compact(|_, _| true).unwrap();
+    assert_eq!(tower.root_digest(), root);
+
+    // A lone kept leaf is its own root
+    let mut tower: LazyTower<Vec<u8>, MockDigest> =
+        LazyTower::from_leaf_digests(2, leaves.clone()).unwrap();
+    tower.compact(|index, _| index == 3).unwrap();
+    assert_eq!(tower.root_digest(), Some(leaves[3].clone()));
+    let proof = tower.generate_proof(0).unwrap();
+    assert!(tower.verify(&proof));
+}
+
+#[test]
+fn test_journal_replay_keeps_digest_leaves() {
+    let mut mixed: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    mixed.enable_journal();
+    mixed.append(b"a".to_vec());
+    for i in 0..4u8 {
+        mixed.append_digest(MockDigest::digest_item(&[i])).unwrap();
+    }
+
+    // A lone digest leaf is its own root after the replay too
+    let mut single: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    single.enable_journal();
+    single.append_digest(MockDigest::digest_item(&[0])).unwrap();
+
+    for tower in [mixed, single] {
+        assert!(tower.journal().iter().any(|entry| entry.digest));
+
+        let empty: LazyTower<Vec<u8>, MockDigest> =
+            LazyTower::from_leaf_digests(2, Vec::new()).unwrap();
+        let replayed = LazyTower::replay_onto(empty, tower.journal()).unwrap();
+        assert_eq!(replayed.root_digest(), tower.root_digest());
+        assert_eq!(replayed.journal(), tower.journal());
+
+        // A tower of plain items cannot take the digests back
+        assert!(matches!(
+            LazyTower::<Vec<u8>, MockDigest>::replay(2, tower.journal()),
+            Err(LazyTowerError::InconsistentState { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_verify_prehashed_matches_verify_for_hashed_leaves() {
+    let options = TowerOptions {
+        hash_leaves: true,
+        ..TowerOptions::default()
+    };
+    let mut tower = LazyTower::<TestItem, MockDigest>::with_options(3, options).unwrap();
+    for i in 0..9 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+    let root = tower.root_digest().unwrap();
+
+    for index in 0..9 {
+        let proof = tower.generate_proof(index).unwrap();
+        let leaf = MockDigest::digest_item(&proof.item);
+        assert!(proof.path.verify(&proof.item, &root));
+        assert!(proof.path.verify_prehashed(&leaf, &root));
+
+        let other = MockDigest::digest_item(&TestItem("other".to_string()));
+        assert!(!proof.path.verify_prehashed(&other, &root));
+    }
+
+    // An empty path compares the leaf with the root directly
+    let leaf = MockDigest::digest_item(&TestItem("solo".to_string()));
+    assert!(ProofPath::<MockDigest>::new().verify_prehashed(&leaf, &leaf));
+}
+
+/// Number of siblings stored across a path's elements
+fn stored_siblings(elements: &[PathElement<MockDigest>]) -> usize {
+    elements
+        .iter()
+        .map(|element| match element {
+            PathElement::Siblings { siblings, .. } => siblings.len(),
+            PathElement::RawSiblings { siblings, .. } => siblings.len(),
+        })
+        .sum()
+}
+
+#[test]
+fn test_sparse_path_on_padded_tower() {
+    let empty = MockDigest::digest_item(b"");
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::new(4).unwrap();
+    for i in 0..16 {
+        let leaf = if i == 0 || i == 9 {
+            MockDigest::digest_item(&format!("leaf{}", i))
+        } else {
+            empty.clone()
+        };
+        tower.append_digest(leaf).unwrap();
+    }
+
+    for index in [0, 9] {
+        let proof = tower.generate_proof(index).unwrap();
+        let sparse = proof.path.sparsify(&empty);
+
+        let full_count = stored_siblings(&proof.path.elements);
+        let sparse_elements: Vec<PathElement<MockDigest>> = sparse
+            .elements
+            .iter()
+            .map(|element| element.element.clone())
+            .collect();
+        assert_eq!(full_count, 6);
+        assert_eq!(stored_siblings(&sparse_elements), 3);
+        assert!(sparse.elements.iter().all(|e| e.sibling_count == 3));
+
+        assert_eq!(sparse.expand(&empty).unwrap(), proof.path);
+        assert!(sparse.verify(&proof.item, &proof.root, &empty));
+        assert!(!sparse.verify(&empty, &proof.root, &empty));
+    }
+
+    // A bitmap claiming more siblings than are kept is rejected
+    let proof = tower.generate_proof(0).unwrap();
+    let mut sparse = proof.path.sparsify(&empty);
+    sparse.elements[0].present[0] |= 0b1;
+    assert!(matches!(
+        sparse.expand(&empty),
+        Err(LazyTowerError::MalformedProof { .. })
+    ));
+    assert!(!sparse.verify(&proof.item, &proof.root, &empty));
+}
+
+#[test]
+fn test_get_mut_refreshes_root_and_proofs() {
+    let hashed = TowerOptions {
+        hash_leaves: true,
+        ..TowerOptions::default()
+    };
+    for options in [TowerOptions::default(), hashed] {
+        let build = |edited: Option<usize>| {
+            let mut tower =
+                LazyTower::<TestItem, MockDigest>::with_options(3, options.clone()).unwrap();
+            for i in 0..29 {
+                let mut name = format!("item{}", i);
+                if Some(i) == edited {
+                    name.push('!');
+                }
+                if i % 4 == 0 {
+                    tower.append_with_meta(TestItem(name), i as u64);
+                } else {
+                    tower.append(TestItem(name));
+                }
+            }
+            tower
+        };
+
+        // Item 5 has been digested twice over; item 27 still waits at level 0
+        for index in [5, 8, 27] {
+            let mut tower = build(None);
+            let old_root = tower.root_digest();
+            tower.get_mut(index).unwrap().0.push('!');
+
+            let expected = build(Some(index));
+            assert_eq!(tower.root_digest(), expected.root_digest());
+            if index < 27 {
+                assert_ne!(tower.root_digest(), old_root);
+            }
+            assert_eq!(tower.get(index), expected.get(index));
+
+            let proof = tower.generate_proof(index).unwrap();
+            assert_eq!(proof.item, TestItem(format!("item{}!", index)));
+            assert_eq!(proof.path, expected.generate_proof(index).unwrap().path);
+            if index < 27 {
+                assert!(tower.verify(&proof));
+            }
+            let neighbour = tower.generate_proof(index ^ 1).unwrap();
+            assert_eq!(
+                neighbour.path,
+                expected.generate_proof(index ^ 1).unwrap().path
+            );
+        }
+    }
+
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(3).unwrap();
+    tower.append(TestItem("a".to_string()));
+    assert!(tower.get_mut(1).is_none());
+}
+
+#[test]
+fn test_append_proof_links_old_and_new_roots() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(4).unwrap();
+    for i in 0..4 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+    let old_root = tower.root_digest().unwrap();
+    tower.append(TestItem("item4".to_string()));
+    let new_root = tower.root_digest().unwrap();
+
+    let proof = tower.append_proof(4).unwrap();
+    assert_eq!(proof.old_count, 4);
+    assert_eq!(proof.new_count(), 5);
+    assert!(proof.verify(&old_root, &new_root));
+
+    // Both levels change once the pending items overflow
+    let mut tower = LazyTower::<TestItem, MockDigest>::with_options(
+        3,
+        TowerOptions {
+            hash_leaves: true,
+            ..TowerOptions::default()
+        },
+    )
+    .unwrap();
+    let mut roots = Vec::new();
+    for i in 0..27 {
+        tower.append(TestItem(format!("item{}", i)));
+        roots.push(tower.root_digest().unwrap());
+    }
+    let new_root = roots[26].clone();
+    for old_count in [1, 5, 9, 20, 27] {
+        let proof = tower.append_proof(old_count).unwrap();
+        assert!(proof.verify(&roots[old_count - 1], &new_root));
+    }
+
+    let proof = tower.append_proof(9).unwrap();
+    assert!(!proof.verify(&roots[7], &new_root));
+    assert!(!proof.verify(&roots[8], &roots[25]));
+
+    let mut tampered = proof.clone();
+    tampered.appended[3] = tampered.appended[4].clone();
+    assert!(!tampered.verify(&roots[8], &new_root));
+
+    let mut miscounted = proof;
+    miscounted.old_count = 10;
+    assert!(!miscounted.verify(&roots[8], &new_root));
+
+    assert!(matches!(
+        tower.append_proof(0),
+        Err(LazyTowerError::InvalidIndex { index: 0, max: 27 })
+    ));
+    assert!(matches!(
+        tower.append_proof(28),
+        Err(LazyTowerError::InvalidIndex { index: 28, max: 27 })
+    ));
+}
+
+#[test]
+fn test_verify_ordered_matches_sibling_order() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(3).unwrap();
+    for i in 0..9 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+    let root = tower.root_digest().unwrap();
+
+    for index in [0, 4, 8] {
+        let item = TestItem(format!("item{}", index));
+        let path = tower.proof_path_for(index).unwrap();
+        let reversed = path.reordered(SiblingOrder::LeftToRight, SiblingOrder::RightToLeft);
+        assert_ne!(reversed, path);
+        assert_eq!(
+            reversed.reordered(SiblingOrder::RightToLeft, SiblingOrder::LeftToRight),
+            path
+        );
+
+        assert!(path.verify_ordered(&item, &root, SiblingOrder::LeftToRight));
+        assert!(reversed.verify_ordered(&item, &root, SiblingOrder::RightToLeft));
+
+        // Each path fails under the other convention
+        assert!(!path.verify_ordered(&item, &root, SiblingOrder::RightToLeft));
+        assert!(!reversed.verify_ordered(&item, &root, SiblingOrder::LeftToRight));
+        assert!(!reversed.verify(&item, &root));
+    }
+}
+
+#[test]
+fn test_group_memo_reuses_digests_of_identical_groups() {
+    let items: Vec<Vec<u8>> = (0..16).map(|i| vec![b'a' + (i % 2) as u8]).collect();
+
+    let mut plain: LazyTower<Vec<u8>, CountingDigest> = LazyTower::new(2).unwrap();
+    let mut memoized: LazyTower<Vec<u8>, CountingDigest> = LazyTower::new(2).unwrap();
+    memoized.enable_group_memo();
+    assert_eq!(plain.group_memo_len(), None);
+
+    let before = CountingDigest::calls();
+    for item in &items {
+        plain.append(item.clone());
+    }
+    let plain_calls = CountingDigest::calls() - before;
+
+    let before = CountingDigest::calls();
+    for item in &items {
+        memoized.append(item.clone());
+    }
+    let memo_calls = CountingDigest::calls() - before;
+
+    // Every group on a level holds the same bytes, so one digest per level
+    assert_eq!(plain_calls, 15);
+    assert_eq!(memo_calls, 4);
+    assert_eq!(memoized.group_memo_len(), Some(4));
+    assert_eq!(memoized.root_digest(), plain.root_digest());
+
+    // Identical groups still attribute each item to its own position
+    for index in 0..items.len() {
+        let proof = memoized.generate_proof(index).unwrap();
+        assert_eq!(proof.path, plain.generate_proof(index).unwrap().path);
+        assert_eq!(proof.proved_index(), Some(index));
+        assert!(proof.verify());
+    }
+}
+
+#[test]
+fn test_semantic_eq_ignores_representation_above_level_zero() {
+    let mut tower = LazyTower::<TestItem, MockDigest>::new(3).unwrap();
+    for i in 0..9 {
+        tower.append(TestItem(format!("item{}", i)));
+    }
+    let proof = tower.generate_proof(4).unwrap();
+    assert!(proof.path.elements.len() > 1);
+
+    // Swap the representation of every element above level 0
+    let flipped = ProofPath::<MockDigest> {
+        elements: proof
+            .path
+            .elements
+            .iter()
+            .enumerate()
+            .map(|(level, element)| match element {
+                _ if level == 0 => element.clone(),
+                PathElement::Siblings { position, siblings } => PathElement::RawSiblings {
+                    position: *position,
+                    siblings: siblings.clone(),
+                },
+                PathElement::RawSiblings { position, siblings } => PathElement::Siblings {
+                    position: *position,
+                    siblings: siblings.clone(),
+                },
+            })
+            .collect(),
+    };
+
+    assert_ne!(flipped, proof.path);
+    assert!(flipped.semantic_eq(&proof.path));
+    assert!(proof.path.semantic_eq(&flipped));
+    assert!(flipped.verify(&proof.item, &proof.root));
+
+    // Level 0 representations fold differently, so they never match
+    let mut level_zero = proof.path.clone();
+    level_zero.elements[0] = match &proof.path.elements[0] {
+        PathElement::Siblings { position, siblings } => PathElement::RawSiblings {
+            position: *position,
+            siblings: siblings.clone(),
+        },
+        PathElement::RawSiblings { position, siblings } => PathElement::Siblings {
+            position: *position,
+            siblings: siblings.clone(),
+        },
+    };
+    assert!(!level_zero.semantic_eq(&proof.path));
+    assert!(!level_zero.verify(&proof.item, &proof.root));
+
+    let other = tower.generate_proof(5).unwrap();
+    assert!(!other.path.semantic_eq(&proof.path));
+}
+
+#[test]
+fn test_string_and_borrowed_items_prove_directly() {
+    use lazytower_rs::BorrowedBytes;
+    use std::borrow::Borrow;
+
+    let names: Vec<String> = (0..9).map(|i| format!("item{}", i)).collect();
+
+    let mut strings = LazyTower::<String, MockDigest>::new(3).unwrap();
+    let mut wrapped = LazyTower::<TestItem, MockDigest>::new(3).unwrap();
+    for name in &names {
+        strings.append(name.clone());
+        wrapped.append(TestItem(name.clone()));
+    }
+    assert_eq!(strings.root_digest(), wrapped.root_digest());
+
+    let proof = strings.generate_proof(4).unwrap();
+    assert_eq!(proof.item, "item4");
+    assert!(proof.verify());
+
+    /// Bytes reachable only through `Borrow<[u8]>`
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct Frame(Vec<u8>);
+
+    impl Borrow<[u8]> for Frame {
+        fn borrow(&self) -> &[u8] {
+            &self.0
+        }
+    }
+
+    let mut frames = LazyTower::<BorrowedBytes<Frame>, MockDigest>::new(3).unwrap();
+    for name in &names {
+        frames
+            .append_borrowed(Frame(name.clone().into_bytes()))
+            .unwrap();
+    }
+    assert_eq!(frames.root_digest(), strings.root_digest());
+
+    let proof = frames.generate_proof(4).unwrap();
+    assert_eq!(proof.item.0, Frame(b"item4".to_vec()));
+    assert!(proof.verify());
+    assert!(frames.verify(&proof));
+}
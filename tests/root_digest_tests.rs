@@ -0,0 +1,333 @@
+//! Tests for root digest calculation
+
+use lazytower_rs::{Digest, LazyTower, TowerOptions, TowerParts};
+
+/// Mock digest for testing
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockDigest;
+
+impl Digest for MockDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut result = b"digest(".to_vec();
+        result.extend_from_slice(item.as_ref());
+        result.extend_from_slice(b")");
+        result
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut result = b"digest_items[".to_vec();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                result.extend_from_slice(b",");
+            }
+            result.extend_from_slice(item.as_ref());
+        }
+        result.extend_from_slice(b"]");
+        result
+    }
+}
+
+/// Create a tower with specified number of items
+fn create_test_tower(num_items: u8, width: usize) -> LazyTower<Vec<u8>, MockDigest> {
+    let mut tower = LazyTower::new(width).unwrap();
+    for i in 0..num_items {
+        tower.append(vec![b'a' + i]);
+    }
+    tower
+}
+
+#[test]
+fn test_parts_round_trip() {
+    let tower = create_test_tower(7, 3);
+    let root = tower.root_digest();
+
+    let parts = tower.into_parts();
+    assert_eq!(parts.widths, vec![3]);
+    assert_eq!(parts.items.len(), 7);
+    assert_eq!(parts.root, root);
+
+    let rebuilt = LazyTower::<Vec<u8>, MockDigest>::from_parts(parts).unwrap();
+    assert_eq!(rebuilt.len(), 7);
+    assert_eq!(rebuilt.root_digest(), root);
+    assert!(rebuilt.validate().is_ok());
+}
+
+#[test]
+fn test_recompute_root_corrects_stale_cached_root() {
+    let fresh = create_test_tower(5, 2);
+    let expected = fresh.root_digest().unwrap();
+
+    let mut parts = fresh.clone().into_parts();
+    parts.root = Some(b"stale".to_vec());
+    let mut imported = LazyTower::<Vec<u8>, MockDigest>::from_parts(parts).unwrap();
+
+    // The imported root is trusted until it is recomputed
+    assert_eq!(imported.root_digest().unwrap(), b"stale");
+
+    assert_eq!(imported.recompute_root().unwrap(), expected);
+    assert_eq!(imported.root_digest().unwrap(), expected);
+}
+
+#[test]
+fn test_append_clears_cached_root() {
+    let parts = TowerParts {
+        widths: vec![2],
+        options: Default::default(),
+        items: vec![b"a".to_vec()],
+        root: Some(b"stale".to_vec()),
+    };
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::from_parts(parts).unwrap();
+
+    tower.append(b"b".to_vec());
+    assert_eq!(tower.root_digest().unwrap(), b"digest_items[a,b]");
+}
+
+#[test]
+fn test_recompute_root_on_empty_tower() {
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::new(4).unwrap();
+    assert_eq!(tower.recompute_root(), None);
+}
+
+#[test]
+fn test_sorted_items_root_is_order_independent() {
+    let forward: Vec<Vec<u8>> = (0..7).map(|i| vec![b'a' + i]).collect();
+    let mut shuffled = forward.clone();
+    shuffled.reverse();
+    shuffled.swap(1, 4);
+
+    let a = LazyTower::<Vec<u8>, MockDigest>::from_sorted_items(2, forward).unwrap();
+    let b = LazyTower::<Vec<u8>, MockDigest>::from_sorted_items(2, shuffled.clone()).unwrap();
+    assert_eq!(a.root_digest(), b.root_digest());
+
+    // Proofs refer to the sorted index
+    assert_eq!(b.get(0), Some(&b"a".to_vec()));
+
+    // An unsorted build of the same items commits to a different root
+    let mut unsorted = LazyTower::<Vec<u8>, MockDigest>::new(2).unwrap();
+    for item in shuffled {
+        unsorted.append(item);
+    }
+    assert_ne!(unsorted.root_digest(), a.root_digest());
+}
+
+#[test]
+fn test_verify_root_against_items() {
+    let items: Vec<Vec<u8>> = (0..23).map(|i| format!("item{}", i).into_bytes()).collect();
+
+    for widths in [vec![2], vec![3], vec![2, 3, 4]] {
+        let mut tower = LazyTower::<Vec<u8>, MockDigest>::new_with_widths(widths).unwrap();
+        assert!(tower.verify_root_against_items(&[]));
+
+        for item in &items {
+            tower.append(item.clone());
+        }
+        assert!(tower.verify_root_against_items(&items));
+
+        let mut altered = items.clone();
+        altered[11] = b"forged".to_vec();
+        assert!(!tower.verify_root_against_items(&altered));
+        assert!(!tower.verify_root_against_items(&items[..22]));
+    }
+}
+
+#[test]
+fn test_rebuild_with_width_preserves_items() {
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::new(2).unwrap();
+    for i in 0..8u8 {
+        tower.append(vec![b'a' + i]);
+    }
+
+    let rebuilt = tower.rebuild_with_width(4).unwrap();
+
+    assert_eq!(rebuilt.width(), 4);
+    assert_eq!(rebuilt.len(), 8);
+    assert_ne!(rebuilt.root_digest(), tower.root_digest());
+    for index in 0..8 {
+        assert_eq!(rebuilt.get(index), tower.get(index));
+        let proof = rebuilt.generate_proof(index).unwrap();
+        assert!(proof.verify());
+        assert_eq!(proof.root, rebuilt.root_digest().unwrap());
+    }
+
+    assert!(tower.rebuild_with_width(1).is_err());
+}
+
+#[test]
+fn test_rebuild_with_width_keeps_leaf_digests() {
+    for count in [1u8, 5, 8] {
+        let leaves: Vec<Vec<u8>> = (0..count).map(|i| MockDigest::digest_item(&[i])).collect();
+        let tower = LazyTower::<Vec<u8>, MockDigest>::from_leaf_digests(4, leaves.clone()).unwrap();
+        let direct = LazyTower::<Vec<u8>, MockDigest>::from_leaf_digests(2, leaves).unwrap();
+
+        let rebuilt = tower.rebuild_with_width(2).unwrap();
+        assert_eq!(
+            rebuilt.root_digest(),
+            direct.root_digest(),
+            "{} leaves",
+            count
+        );
+        let proof = rebuilt.generate_proof(0).unwrap();
+        assert!(rebuilt.verify(&proof));
+    }
+}
+
+#[test]
+fn test_verify_root_against_items_with_metadata() {
+    let items: Vec<Vec<u8>> = (0..11)
+        .map(|i| format!("event{}", i).into_bytes())
+        .collect();
+
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::new(3).unwrap();
+    for (i, item) in items.iter().enumerate() {
+        if i % 4 == 0 {
+            tower.append(item.clone());
+        } else {
+            tower.append_with_meta(item.clone(), i as u64);
+        }
+        assert!(
+            tower.verify_root_against_items(&items[..=i]),
+            "{} items",
+            i + 1
+        );
+    }
+
+    let mut altered = items.clone();
+    altered[5] = b"forged".to_vec();
+    assert!(!tower.verify_root_against_items(&altered));
+
+    let mut lone = LazyTower::<Vec<u8>, MockDigest>::new(3).unwrap();
+    lone.append_with_meta(items[0].clone(), 9);
+    assert!(lone.verify_root_against_items(&items[..1]));
+}
+
+#[test]
+fn test_verify_root_against_items_with_leaf_digests() {
+    let leaves: Vec<Vec<u8>> = (0..9u8).map(|i| MockDigest::digest_item(&[i])).collect();
+
+    for count in 1..=leaves.len() {
+        let tower =
+            LazyTower::<Vec<u8>, MockDigest>::from_leaf_digests(3, leaves[..count].to_vec())
+                .unwrap();
+        assert!(
+            tower.verify_root_against_items(&leaves[..count]),
+            "{} leaves",
+            count
+        );
+    }
+
+    // The lone leaf is compared as it is, so other bytes still fail
+    let lone =
+        LazyTower::<Vec<u8>, MockDigest>::from_leaf_digests(3, vec![leaves[0].clone()]).unwrap();
+    assert!(!lone.verify_root_against_items(&leaves[1..2]));
+}
+
+#[test]
+fn test_leaf_digests_in_index_order() {
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::new(3).unwrap();
+    assert!(tower.leaf_digests().is_empty());
+
+    for i in 0..10u8 {
+        tower.append(vec![b'a' + i]);
+    }
+
+    let leaves = tower.leaf_digests();
+    assert_eq!(leaves.len(), tower.len());
+    for (index, leaf) in leaves.iter().enumerate() {
+        assert_eq!(leaf, &MockDigest::digest_item(tower.get(index).unwrap()));
+    }
+}
+
+#[test]
+fn test_leaf_digests_apply_nonce() {
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::new_with_nonce(3, b"n".to_vec()).unwrap();
+    for i in 0..5u8 {
+        tower.append(vec![b'a' + i]);
+    }
+
+    let leaves = tower.leaf_digests();
+    for (index, leaf) in leaves.iter().enumerate() {
+        let nonced = MockDigest::digest_items(&[b"n".as_slice(), tower.get(index).unwrap()]);
+        assert_eq!(leaf, &MockDigest::digest_item(&nonced));
+    }
+}
+
+#[test]
+fn test_cached_root_tracks_every_mutation() {
+    // Start from an import so the first root is served from the cache
+    let fresh = {
+        let mut tower = LazyTower::<Vec<u8>, MockDigest>::new(3).unwrap();
+        tower.append(b"seed".to_vec());
+        tower
+    };
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::from_parts(fresh.into_parts()).unwrap();
+
+    let mut state = 7usize;
+    for step in 0..50 {
+        state = (state * 1103515245 + 12345) % 2147483648;
+        let item = format!("item{}", step).into_bytes();
+        match state % 4 {
+            0 => tower.append(item),
+            1 => tower.append_with_meta(item, step as u64),
+            2 => {
+                tower
+                    .append_batch_atomic(vec![item, b"extra".to_vec()])
+                    .unwrap();
+            }
+            _ => tower.flush(),
+        }
+
+        let mut scratch = tower.clone();
+        assert_eq!(
+            tower.root_digest(),
+            scratch.recompute_root(),
+            "step {}",
+            step
+        );
+        assert!(tower.validate().is_ok());
+    }
+}
+
+#[test]
+fn test_single_item_root_is_its_digest() {
+    let item = b"only".to_vec();
+    let expected = MockDigest::digest_item(&item);
+
+    for width in [2, 3, 4, 16] {
+        let mut tower = LazyTower::<Vec<u8>, MockDigest>::new(width).unwrap();
+        tower.append(item.clone());
+        assert_eq!(tower.root_digest(), Some(expected.clone()));
+        assert_ne!(
+            tower.root_digest(),
+            Some(MockDigest::digest_items(&[&item]))
+        );
+
+        let proof = tower.generate_proof(0).unwrap();
+        assert!(proof.path.elements.is_empty());
+        assert_eq!(proof.root, expected);
+        assert!(proof.verify());
+        assert!(tower.verify(&proof));
+        assert!(tower.verify_root_against_items(std::slice::from_ref(&item)));
+
+        // Flushing leaves a lone top node alone
+        tower.flush();
+        assert_eq!(tower.root_digest(), Some(expected.clone()));
+        assert_eq!(tower.recompute_root(), Some(expected.clone()));
+    }
+
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::new_with_widths(vec![3, 2]).unwrap();
+    tower.append(item.clone());
+    assert_eq!(tower.root_digest(), Some(expected.clone()));
+    assert!(tower.generate_proof(0).unwrap().verify());
+
+    // A hashed leaf is the lone node, which is the same digest
+    let options = TowerOptions {
+        hash_leaves: true,
+        ..TowerOptions::default()
+    };
+    let mut tower = LazyTower::<Vec<u8>, MockDigest>::with_options(4, options).unwrap();
+    tower.append(item.clone());
+    assert_eq!(tower.root_digest(), Some(expected));
+    assert!(tower.generate_proof(0).unwrap().verify());
+}
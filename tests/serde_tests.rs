@@ -0,0 +1,185 @@
+//! Tests for proof serialization
+
+#![cfg(feature = "serde")]
+
+use lazytower_rs::{Digest, LazyTower, LazyTowerError, MembershipProof, ProofBundle};
+
+/// Mock digest for testing
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockDigest;
+
+impl Digest for MockDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut result = b"digest(".to_vec();
+        result.extend_from_slice(item.as_ref());
+        result.extend_from_slice(b")");
+        result
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut result = b"digest_items[".to_vec();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                result.extend_from_slice(b",");
+            }
+            result.extend_from_slice(item.as_ref());
+        }
+        result.extend_from_slice(b"]");
+        result
+    }
+}
+
+#[test]
+fn test_proof_json_round_trip() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    for i in 0..4 {
+        tower.append(vec![i]);
+    }
+
+    let proof = tower.generate_proof(2).unwrap();
+    let json = proof.to_json().unwrap();
+    let decoded = MembershipProof::<Vec<u8>, MockDigest>::from_json(&json).unwrap();
+
+    assert_eq!(decoded.item, proof.item);
+    assert_eq!(decoded.path, proof.path);
+    assert_eq!(decoded.root, proof.root);
+    assert!(decoded.verify());
+}
+
+#[test]
+fn test_proof_from_malformed_json() {
+    let result = MembershipProof::<Vec<u8>, MockDigest>::from_json("{\"item\": [1, 2");
+
+    match result {
+        Err(LazyTowerError::Serialization { .. }) => {}
+        other => panic!("Expected Serialization error, got {:?}", other),
+    }
+}
+
+/// Read an unsigned LEB128 varint from `bytes` at `offset`
+fn read_varint(bytes: &[u8], offset: &mut usize) -> usize {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = bytes[*offset];
+        *offset += 1;
+        value |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return value;
+        }
+        shift += 7;
+    }
+}
+
+/// Verify a JSON proof bundle without using any verification code from the crate
+fn verify_bundle_by_hand(json: &str) -> bool {
+    let bundle: serde_json::Value = serde_json::from_str(json).unwrap();
+    let bytes = |field: &str| -> Vec<u8> {
+        bundle[field]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| b.as_u64().unwrap() as u8)
+            .collect()
+    };
+    let item = bytes("item");
+    let path = bytes("path");
+    let root = bytes("root");
+
+    let mut offset = 0;
+    let mut current: Option<Vec<u8>> = None;
+    for element in 0..read_varint(&path, &mut offset) {
+        let tag = path[offset];
+        offset += 1;
+        let position = read_varint(&path, &mut offset);
+
+        let mut group = Vec::new();
+        for _ in 0..read_varint(&path, &mut offset) {
+            let len = read_varint(&path, &mut offset);
+            group.push(path[offset..offset + len].to_vec());
+            offset += len;
+        }
+
+        // Raw siblings at the bottom combine with the item itself
+        let node = match (&current, tag, element) {
+            (None, 1, 0) => item.clone(),
+            (None, _, _) => MockDigest::digest_item(&item),
+            (Some(digest), _, _) => digest.clone(),
+        };
+        group.insert(position, node);
+        current = Some(MockDigest::digest_items(&group));
+    }
+
+    current.unwrap_or_else(|| MockDigest::digest_item(&item)) == root
+}
+
+#[test]
+fn test_exported_bundle_verifies_without_crate() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(3).unwrap();
+    for i in 0..20u8 {
+        tower.append(vec![b'a' + i]);
+    }
+
+    for index in [0, 4, 9, 17] {
+        let bundle = tower.export_proof_bundle(index).unwrap();
+        assert_eq!(bundle.width, 3);
+        assert_eq!(bundle.algorithm, "unknown");
+
+        let json = bundle.to_json().unwrap();
+        assert!(verify_bundle_by_hand(&json));
+        assert_eq!(ProofBundle::from_json(&json).unwrap(), bundle);
+    }
+
+    // A tampered item is rejected
+    let mut bundle = tower.export_proof_bundle(4).unwrap();
+    bundle.item = b"z".to_vec();
+    assert!(!verify_bundle_by_hand(&bundle.to_json().unwrap()));
+}
+
+#[test]
+fn test_json_leaf_encoder_proves_canonical_encoding() {
+    use lazytower_rs::{Encoded, LeafEncoder};
+    use serde::Serialize;
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+    struct Account {
+        name: String,
+        balance: u64,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct JsonEncoder;
+
+    impl LeafEncoder<Account> for JsonEncoder {
+        fn encode(value: &Account) -> Vec<u8> {
+            serde_json::to_vec(value).unwrap()
+        }
+    }
+
+    type Leaf = Encoded<Account, JsonEncoder>;
+
+    let account = |name: &str, balance| Account {
+        name: name.to_string(),
+        balance,
+    };
+    let mut tower: LazyTower<Leaf, MockDigest> = LazyTower::new_with_encoder(2).unwrap();
+    for (name, balance) in [("alice", 10), ("bob", 20), ("carol", 30), ("dave", 40)] {
+        tower.append_value(account(name, balance)).unwrap();
+    }
+
+    let stored = tower.get(2).unwrap();
+    assert_eq!(stored.value(), &account("carol", 30));
+    assert_eq!(stored.as_ref(), br#"{"name":"carol","balance":30}"#);
+
+    let proof = tower.generate_proof(2).unwrap();
+    assert!(tower.verify(&proof));
+
+    // A verifier re-encodes the value it was given
+    let root = tower.root_digest().unwrap();
+    let claimed = Leaf::new(account("carol", 30));
+    assert!(proof.path.verify(&claimed, &root));
+    let forged = Leaf::new(account("carol", 31));
+    assert!(!proof.path.verify(&forged, &root));
+}
@@ -0,0 +1,135 @@
+//! Tests for item-free tower skeletons
+
+use lazytower_rs::{Digest, LazyTower, LazyTowerError};
+
+/// Mock digest for testing
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockDigest;
+
+impl Digest for MockDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut result = b"digest(".to_vec();
+        result.extend_from_slice(item.as_ref());
+        result.extend_from_slice(b")");
+        result
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut result = b"digest_items[".to_vec();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                result.extend_from_slice(b",");
+            }
+            result.extend_from_slice(item.as_ref());
+        }
+        result.extend_from_slice(b"]");
+        result
+    }
+}
+
+#[test]
+fn test_skeleton_proofs_verify_with_external_items() {
+    let items: Vec<Vec<u8>> = (0..8).map(|i| format!("item{}", i).into_bytes()).collect();
+
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    for item in &items {
+        tower.append(item.clone());
+    }
+
+    let skeleton = tower.skeleton();
+    assert_eq!(skeleton.len(), 8);
+    assert_eq!(skeleton.width(), 2);
+    assert_eq!(skeleton.root_digest(), tower.root_digest().as_ref());
+
+    for i in 0..items.len() {
+        let proof = skeleton.prove(i, &items).unwrap();
+        assert_eq!(proof.item, items[i]);
+        assert!(proof.verify(), "Skeleton proof failed for item {}", i);
+    }
+}
+
+#[test]
+fn test_skeleton_proofs_match_tower_proofs() {
+    let items: Vec<Vec<u8>> = (0..11).map(|i| vec![b'a' + i]).collect();
+
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(3).unwrap();
+    for item in &items {
+        tower.append(item.clone());
+    }
+
+    let skeleton = tower.skeleton();
+    for i in 0..items.len() {
+        let expected = tower.generate_proof(i).unwrap();
+        let proof = skeleton.prove(i, &items).unwrap();
+        assert_eq!(proof.path, expected.path);
+        assert_eq!(proof.root, expected.root);
+    }
+}
+
+#[test]
+fn test_skeleton_proofs_verify_for_nonced_tower() {
+    let items: Vec<Vec<u8>> = (0..11).map(|i| vec![b'a' + i]).collect();
+
+    let mut tower: LazyTower<Vec<u8>, MockDigest> =
+        LazyTower::new_with_nonce(3, b"nonce".to_vec()).unwrap();
+    for item in &items {
+        tower.append(item.clone());
+    }
+
+    let skeleton = tower.skeleton();
+    for i in 0..items.len() {
+        let expected = tower.generate_proof(i).unwrap();
+        let proof = skeleton.prove(i, &items).unwrap();
+        assert_eq!(proof.item, items[i]);
+        assert_eq!(proof.nonce, Some(b"nonce".to_vec()));
+        assert_eq!(proof.path, expected.path);
+        assert_eq!(proof.verify(), expected.verify(), "item {}", i);
+    }
+
+    // Items already digested into the top level verify against the root
+    for i in 0..9 {
+        assert!(skeleton.prove(i, &items).unwrap().verify(), "item {}", i);
+    }
+}
+
+#[test]
+fn test_skeleton_proofs_carry_item_metadata() {
+    let items: Vec<Vec<u8>> = (0..9).map(|i| format!("event{}", i).into_bytes()).collect();
+
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(3).unwrap();
+    for (i, item) in items.iter().enumerate() {
+        if i == 4 {
+            tower.append(item.clone());
+        } else {
+            tower.append_with_meta(item.clone(), 1_700_000_000 + i as u64);
+        }
+    }
+
+    let skeleton = tower.skeleton();
+    for i in 0..items.len() {
+        let proof = skeleton.prove(i, &items).unwrap();
+        assert_eq!(proof.meta, tower.meta(i));
+        assert_eq!(proof.path, tower.generate_proof(i).unwrap().path);
+        assert!(proof.verify(), "Skeleton proof failed for item {}", i);
+    }
+}
+
+#[test]
+fn test_skeleton_prove_errors() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    tower.append(b"A".to_vec());
+    tower.append(b"B".to_vec());
+
+    let skeleton = tower.skeleton();
+
+    assert_eq!(
+        skeleton.prove(2, &[b"A", b"B"]).unwrap_err(),
+        LazyTowerError::InvalidIndex { index: 2, max: 2 }
+    );
+    assert_eq!(
+        skeleton.prove(1, &[b"A"]).unwrap_err(),
+        LazyTowerError::MissingItemBytes { index: 1 }
+    );
+}
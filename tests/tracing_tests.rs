@@ -0,0 +1,53 @@
+//! Tests for tracing instrumentation
+
+#![cfg(feature = "tracing")]
+
+use lazytower_rs::{Digest, LazyTower};
+use tracing_test::traced_test;
+
+/// Mock digest for testing
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockDigest;
+
+impl Digest for MockDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut result = b"digest(".to_vec();
+        result.extend_from_slice(item.as_ref());
+        result.extend_from_slice(b")");
+        result
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut result = b"digest_items[".to_vec();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                result.extend_from_slice(b",");
+            }
+            result.extend_from_slice(item.as_ref());
+        }
+        result.extend_from_slice(b"]");
+        result
+    }
+}
+
+#[test]
+#[traced_test]
+fn test_overflow_events_emitted() {
+    let mut tower: LazyTower<Vec<u8>, MockDigest> = LazyTower::new(2).unwrap();
+    tower.append(vec![0]);
+    assert!(!logs_contain("level overflowed"));
+
+    // The fourth item overflows level 0 and then level 1
+    for i in 1..4u8 {
+        tower.append(vec![i]);
+    }
+    assert!(logs_contain("level overflowed level=0"));
+    assert!(logs_contain("level overflowed level=1"));
+    assert!(logs_contain("append{index=3}"));
+
+    tower.generate_proof(2).unwrap();
+    assert!(logs_contain("generate_proof{index=2}"));
+    assert!(logs_contain("proof path built path_len=2"));
+}
@@ -0,0 +1,74 @@
+//! Tests for NFC normalization of string items
+
+#![cfg(feature = "unicode")]
+
+use lazytower_rs::{Digest, Encoded, LazyTower, LeafEncoder, Utf8Encoder};
+
+/// Mock digest for testing
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockDigest;
+
+impl Digest for MockDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut result = b"digest(".to_vec();
+        result.extend_from_slice(item.as_ref());
+        result.extend_from_slice(b")");
+        result
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut result = b"digest_items[".to_vec();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                result.extend_from_slice(b",");
+            }
+            result.extend_from_slice(item.as_ref());
+        }
+        result.extend_from_slice(b"]");
+        result
+    }
+}
+
+/// "café" with a precomposed "é"
+const COMPOSED: &str = "caf\u{e9}";
+/// "café" with "e" followed by a combining acute accent
+const DECOMPOSED: &str = "cafe\u{301}";
+
+fn leaf_digests<const NORMALIZE_UTF8: bool>() -> Vec<Vec<u8>>
+where
+    Utf8Encoder<NORMALIZE_UTF8>: LeafEncoder<&'static str>,
+{
+    let mut tower: LazyTower<Encoded<&'static str, Utf8Encoder<NORMALIZE_UTF8>>, MockDigest> =
+        LazyTower::new_with_encoder(2).unwrap();
+    tower.append_value(COMPOSED).unwrap();
+    tower.append_value(DECOMPOSED).unwrap();
+    tower.leaf_digests()
+}
+
+#[test]
+fn test_normalization_merges_equivalent_strings() {
+    assert_ne!(COMPOSED, DECOMPOSED);
+
+    let normalized = leaf_digests::<true>();
+    assert_eq!(normalized[0], normalized[1]);
+    assert_eq!(normalized[0], MockDigest::digest_item(&COMPOSED));
+
+    let raw = leaf_digests::<false>();
+    assert_ne!(raw[0], raw[1]);
+    assert_eq!(raw[1], MockDigest::digest_item(&DECOMPOSED));
+}
+
+#[test]
+fn test_normalized_items_keep_their_value() {
+    let mut tower: LazyTower<Encoded<String, Utf8Encoder<true>>, MockDigest> =
+        LazyTower::new_with_encoder(2).unwrap();
+    tower.append_value(DECOMPOSED.to_string()).unwrap();
+    tower.append_value("tea".to_string()).unwrap();
+
+    let proof = tower.generate_proof(0).unwrap();
+    assert_eq!(proof.item.value(), DECOMPOSED);
+    assert_eq!(proof.item.as_ref(), COMPOSED.as_bytes());
+    assert!(proof.verify());
+}
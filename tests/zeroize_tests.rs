@@ -0,0 +1,124 @@
+//! Tests for wiping stored items
+
+#![cfg(feature = "zeroize")]
+
+use lazytower_rs::{Digest, LazyTower};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Mock digest for testing
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MockDigest;
+
+impl Digest for MockDigest {
+    type Output = Vec<u8>;
+
+    fn digest_item<T: AsRef<[u8]>>(item: &T) -> Self::Output {
+        let mut result = b"digest(".to_vec();
+        result.extend_from_slice(item.as_ref());
+        result.extend_from_slice(b")");
+        result
+    }
+
+    fn digest_items<T: AsRef<[u8]>>(items: &[T]) -> Self::Output {
+        let mut result = b"digest_items[".to_vec();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                result.extend_from_slice(b",");
+            }
+            result.extend_from_slice(item.as_ref());
+        }
+        result.extend_from_slice(b"]");
+        result
+    }
+}
+
+/// Secret bytes that count how often a copy of them is wiped
+#[derive(Clone, Debug)]
+struct Secret {
+    bytes: Vec<u8>,
+    wiped: Arc<AtomicUsize>,
+}
+
+impl AsRef<[u8]> for Secret {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl Zeroize for Secret {
+    fn zeroize(&mut self) {
+        self.bytes.zeroize();
+        self.wiped.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn secrets(count: u8, wiped: &Arc<AtomicUsize>) -> Vec<Secret> {
+    (0..count)
+        .map(|i| Secret {
+            bytes: format!("secret{}", i).into_bytes(),
+            wiped: Arc::clone(wiped),
+        })
+        .collect()
+}
+
+#[test]
+fn test_clear_zeroized_wipes_every_copy() {
+    let wiped = Arc::new(AtomicUsize::new(0));
+    let mut tower = LazyTower::<Secret, MockDigest>::new(4).unwrap();
+    tower.enable_journal();
+    tower.enable_proof_cache();
+    for secret in secrets(5, &wiped) {
+        tower.append(secret);
+    }
+    // Item 4 still waits at level 0; both proofs are cached
+    assert!(tower.generate_proof(0).unwrap().verify());
+    assert!(tower.generate_proof(4).is_ok());
+
+    tower.clear_zeroized();
+
+    // Five stored items, the one still at level 0 and two cached proofs
+    assert_eq!(wiped.load(Ordering::SeqCst), 8);
+    assert!(tower.is_empty());
+    assert_eq!(tower.root_digest(), None);
+    assert!(tower.journal().is_empty());
+
+    tower.append(secrets(1, &wiped).remove(0));
+    assert_eq!(tower.len(), 1);
+    assert_eq!(tower.journal().len(), 1);
+}
+
+#[test]
+fn test_clear_zeroized_leaves_shared_storage_to_clone() {
+    let wiped = Arc::new(AtomicUsize::new(0));
+    let mut tower = LazyTower::<Secret, MockDigest>::new(4).unwrap();
+    for secret in secrets(6, &wiped) {
+        tower.append(secret);
+    }
+    let copy = tower.clone();
+
+    tower.clear_zeroized();
+
+    assert_eq!(wiped.load(Ordering::SeqCst), 0);
+    assert!(tower.is_empty());
+    assert_eq!(copy.len(), 6);
+    assert_eq!(copy.get(5).unwrap().as_ref(), b"secret5");
+    assert!(copy.generate_proof(3).unwrap().verify());
+}
+
+#[test]
+fn test_self_zeroizing_items_are_wiped_on_drop() {
+    let wiped = Arc::new(AtomicUsize::new(0));
+    let mut tower = LazyTower::<Zeroizing<Secret>, MockDigest>::new(4).unwrap();
+    for secret in secrets(6, &wiped) {
+        tower.append(Zeroizing::new(secret));
+    }
+    // The level 0 copies of the first four were wiped as they overflowed
+    assert_eq!(wiped.load(Ordering::SeqCst), 4);
+
+    drop(tower);
+
+    // Six stored items and the two still at level 0
+    assert_eq!(wiped.load(Ordering::SeqCst), 12);
+}